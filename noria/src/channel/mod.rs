@@ -30,22 +30,40 @@ pub struct MaybeLocal;
 pub struct DomainConnectionBuilder<D, T> {
     sport: Option<u16>,
     addr: SocketAddr,
-    chan: Option<tokio::sync::mpsc::UnboundedSender<T>>,
+    chan: Option<LocalChannel<T>>,
     is_for_base: bool,
     _marker: D,
 }
 
-struct ImplSinkForSender<T>(tokio::sync::mpsc::UnboundedSender<T>);
+/// The channels through which a domain in the same process can be reached.
+pub struct LocalChannel<T> {
+    /// Used for synchronous sends, which can't wait for the domain to catch up.
+    pub control: tokio::sync::mpsc::UnboundedSender<T>,
+    /// Used for sends from other domains, which wait while the domain is too far behind.
+    pub data: tokio::sync::mpsc::Sender<T>,
+}
+
+impl<T> Clone for LocalChannel<T> {
+    fn clone(&self) -> Self {
+        LocalChannel {
+            control: self.control.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+struct ImplSinkForSender<T>(tokio::sync::mpsc::Sender<T>);
 
 impl<T> Sink<T> for ImplSinkForSender<T> {
-    type Error = tokio::sync::mpsc::error::SendError<T>;
+    type Error = ();
 
-    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx).map_err(|_| ())
     }
 
-    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
-        self.0.send(item)
+    fn start_send(mut self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        // the successful poll_ready reserved a slot for this send
+        self.0.try_send(item).map_err(|_| ())
     }
 
     fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -143,7 +161,7 @@ where
     ) -> io::Result<Box<dyn Sink<T, Error = bincode::Error> + Send + Unpin>> {
         if let Some(chan) = self.chan {
             Ok(Box::new(
-                ImplSinkForSender(chan)
+                ImplSinkForSender(chan.data)
                     .sink_map_err(|_| serde::de::Error::custom("failed to do local send")),
             ) as Box<_>)
         } else {
@@ -161,7 +179,7 @@ where
 
     pub fn build_sync(self) -> io::Result<Box<dyn Sender<Item = T> + Send>> {
         if let Some(chan) = self.chan {
-            Ok(Box::new(chan))
+            Ok(Box::new(chan.control))
         } else {
             DomainConnectionBuilder {
                 sport: self.sport,
@@ -180,7 +198,7 @@ struct ChannelCoordinatorInner<K: Eq + Hash + Clone, T> {
     /// Map from key to remote address.
     addrs: HashMap<K, SocketAddr>,
     /// Map from key to channel sender for local connections.
-    locals: HashMap<K, tokio::sync::mpsc::UnboundedSender<T>>,
+    locals: HashMap<K, LocalChannel<T>>,
 }
 
 pub struct ChannelCoordinator<K: Eq + Hash + Clone, T> {
@@ -208,7 +226,7 @@ impl<K: Eq + Hash + Clone, T> ChannelCoordinator<K, T> {
         inner.addrs.insert(key, addr);
     }
 
-    pub fn insert_local(&self, key: K, chan: tokio::sync::mpsc::UnboundedSender<T>) {
+    pub fn insert_local(&self, key: K, chan: LocalChannel<T>) {
         let mut inner = self.inner.write().unwrap();
         inner.locals.insert(key, chan);
     }
@@ -3,7 +3,7 @@ use std::io::{self, Write};
 use std::marker::PhantomData;
 use std::net::{Ipv4Addr, SocketAddr};
 
-use crate::Tagged;
+use crate::{Tagged, WriteReply};
 use async_bincode::{AsyncBincodeStream, AsyncDestination};
 use bufstream::BufStream;
 use byteorder::{NetworkEndian, WriteBytesExt};
@@ -158,9 +158,9 @@ pub enum RecvError {
 
 #[pin_project(project = DualTcpStreamProj)]
 pub enum DualTcpStream<S, T, T2, D> {
-    Passthrough(#[pin] AsyncBincodeStream<S, T, Tagged<()>, D>),
+    Passthrough(#[pin] AsyncBincodeStream<S, T, Tagged<WriteReply>, D>),
     Upgrade(
-        #[pin] AsyncBincodeStream<S, T2, Tagged<()>, D>,
+        #[pin] AsyncBincodeStream<S, T2, Tagged<WriteReply>, D>,
         Box<dyn FnMut(T2) -> T + Send + Sync>,
     ),
 }
@@ -173,7 +173,7 @@ impl<S, T, T2> From<S> for DualTcpStream<S, T, T2, AsyncDestination> {
 
 impl<S, T, T2> DualTcpStream<S, T, T2, AsyncDestination> {
     pub fn upgrade<F: 'static + FnMut(T2) -> T + Send + Sync>(stream: S, f: F) -> Self {
-        let s: AsyncBincodeStream<S, T2, Tagged<()>, AsyncDestination> =
+        let s: AsyncBincodeStream<S, T2, Tagged<WriteReply>, AsyncDestination> =
             AsyncBincodeStream::from(stream).for_async();
         DualTcpStream::Upgrade(s, Box::new(f))
     }
@@ -186,11 +186,13 @@ impl<S, T, T2> DualTcpStream<S, T, T2, AsyncDestination> {
     }
 }
 
-impl<S, T, T2, D> Sink<Tagged<()>> for DualTcpStream<S, T, T2, D>
+impl<S, T, T2, D> Sink<Tagged<WriteReply>> for DualTcpStream<S, T, T2, D>
 where
     S: AsyncWrite,
-    AsyncBincodeStream<S, T, Tagged<()>, D>: Sink<Tagged<()>, Error = bincode::Error>,
-    AsyncBincodeStream<S, T2, Tagged<()>, D>: Sink<Tagged<()>, Error = bincode::Error>,
+    AsyncBincodeStream<S, T, Tagged<WriteReply>, D>:
+        Sink<Tagged<WriteReply>, Error = bincode::Error>,
+    AsyncBincodeStream<S, T2, Tagged<WriteReply>, D>:
+        Sink<Tagged<WriteReply>, Error = bincode::Error>,
 {
     type Error = bincode::Error;
 
@@ -201,7 +203,7 @@ where
         }
     }
 
-    fn start_send(self: Pin<&mut Self>, item: Tagged<()>) -> Result<(), Self::Error> {
+    fn start_send(self: Pin<&mut Self>, item: Tagged<WriteReply>) -> Result<(), Self::Error> {
         match self.project() {
             DualTcpStreamProj::Passthrough(abs) => abs.start_send(item),
             DualTcpStreamProj::Upgrade(abs, _) => abs.start_send(item),
@@ -228,8 +230,8 @@ where
     for<'a> T: Deserialize<'a>,
     for<'a> T2: Deserialize<'a>,
     S: AsyncRead,
-    AsyncBincodeStream<S, T, Tagged<()>, D>: Stream<Item = Result<T, bincode::Error>>,
-    AsyncBincodeStream<S, T2, Tagged<()>, D>: Stream<Item = Result<T2, bincode::Error>>,
+    AsyncBincodeStream<S, T, Tagged<WriteReply>, D>: Stream<Item = Result<T, bincode::Error>>,
+    AsyncBincodeStream<S, T2, Tagged<WriteReply>, D>: Stream<Item = Result<T2, bincode::Error>>,
 {
     type Item = Result<T, bincode::Error>;
 
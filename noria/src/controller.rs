@@ -1,5 +1,5 @@
 use crate::consensus::{self, Authority};
-use crate::debug::stats;
+use crate::debug::{explain, stats, trace};
 use crate::table::{Table, TableBuilder, TableRpc};
 use crate::view::{View, ViewBuilder, ViewRpc};
 use crate::ActivationResult;
@@ -379,6 +379,29 @@ impl<A: Authority + 'static> ControllerHandle<A> {
         self.rpc("get_statistics", (), "failed to get stats")
     }
 
+    /// Describe the operators that compute the view with the given name.
+    ///
+    /// `Self::poll_ready` must have returned `Async::Ready` before you call this method.
+    pub fn explain(
+        &mut self,
+        view: &str,
+    ) -> impl Future<Output = Result<explain::Plan, failure::Error>> {
+        self.rpc("explain", view, "failed to explain view")
+    }
+
+    /// Fetch the events recorded for writes issued with the given trace id.
+    ///
+    /// Events are ordered by the time they were recorded. Each domain only keeps a bounded
+    /// number of events, so traces for old writes may be incomplete or missing.
+    ///
+    /// `Self::poll_ready` must have returned `Async::Ready` before you call this method.
+    pub fn trace(
+        &mut self,
+        trace: u64,
+    ) -> impl Future<Output = Result<Vec<trace::TraceEvent>, failure::Error>> {
+        self.rpc("trace", trace, "failed to fetch trace")
+    }
+
     /// Flush all partial state, evicting all rows present.
     ///
     /// `Self::poll_ready` must have returned `Async::Ready` before you call this method.
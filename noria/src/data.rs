@@ -1,17 +1,24 @@
 use arccstr::ArcCStr;
 
-use chrono::{self, NaiveDate, NaiveDateTime};
+use chrono::{self, DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 
-use nom_sql::Literal;
+use nom_sql::{Literal, SqlType};
 
 use std::convert::TryFrom;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::ops::{Add, Div, Mul, Sub};
+use std::sync::Arc;
 
 const FLOAT_PRECISION: f64 = 1_000_000_000.0;
 const TINYTEXT_WIDTH: usize = 15;
 
+/// The maximum number of digits to the right of the decimal point in a `DataType::Decimal`.
+pub const MAX_DECIMAL_SCALE: u8 = 18;
+
+/// The number of extra digits kept when dividing decimals.
+const DECIMAL_DIV_INCREMENT: u8 = 4;
+
 /// The main type used for user data throughout the codebase.
 ///
 /// Having this be an enum allows for our code to be agnostic about the types of user data except
@@ -20,7 +27,7 @@ const TINYTEXT_WIDTH: usize = 15;
 /// Note that cloning a `DataType` using the `Clone` trait is possible, but may result in cache
 /// contention on the reference counts for de-duplicated strings. Use `DataType::deep_clone` to
 /// clone the *value* of a `DataType` without danger of contention.
-#[derive(Eq, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[warn(variant_size_differences)]
 pub enum DataType {
     /// An empty value.
@@ -42,6 +49,20 @@ pub enum DataType {
     TinyText([u8; TINYTEXT_WIDTH]),
     /// A timestamp for date/time types.
     Timestamp(NaiveDateTime),
+    /// An exact fixed point decimal value. The first field is the unscaled value, while the second
+    /// is the number of digits to the right of the decimal point (at most `MAX_DECIMAL_SCALE`).
+    Decimal(i64, u8),
+    /// A reference-counted opaque binary value.
+    Bytes(Arc<Vec<u8>>),
+    /// A 128-bit universally unique identifier. The bytes are reference-counted so that a
+    /// `DataType` stays two words wide.
+    Uuid(Arc<[u8; 16]>),
+    /// A boolean value.
+    Bool(bool),
+    /// A double-precision floating point value with a total order, so that it can be used as a
+    /// key. All NaNs are equal to each other and greater than every other value, and `-0.0` is
+    /// equal to `0.0`.
+    Float(f64),
 }
 
 impl fmt::Display for DataType {
@@ -66,6 +87,40 @@ impl fmt::Display for DataType {
                 }
             }
             DataType::Timestamp(ts) => write!(f, "{}", ts.format("%c")),
+            DataType::Decimal(m, scale) => {
+                if scale == 0 {
+                    return write!(f, "{}", m);
+                }
+                let p = 10u64.pow(u32::from(scale));
+                let sign = if m < 0 { "-" } else { "" };
+                let m = m.unsigned_abs();
+                write!(
+                    f,
+                    "{}{}.{:0width$}",
+                    sign,
+                    m / p,
+                    m % p,
+                    width = usize::from(scale)
+                )
+            }
+            DataType::Bytes(ref b) => {
+                write!(f, "0x")?;
+                for byte in b.iter() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            DataType::Bool(b) => write!(f, "{}", b),
+            DataType::Float(v) => write!(f, "{}", v),
+            DataType::Uuid(ref u) => {
+                for (i, byte) in u.iter().enumerate() {
+                    if i == 4 || i == 6 || i == 8 || i == 10 {
+                        write!(f, "-")?;
+                    }
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -84,6 +139,11 @@ impl fmt::Debug for DataType {
             }
             DataType::Timestamp(ts) => write!(f, "Timestamp({:?})", ts),
             DataType::Real(..) => write!(f, "Real({})", self),
+            DataType::Decimal(..) => write!(f, "Decimal({})", self),
+            DataType::Bytes(..) => write!(f, "Bytes({})", self),
+            DataType::Uuid(..) => write!(f, "Uuid({})", self),
+            DataType::Bool(b) => write!(f, "Bool({})", b),
+            DataType::Float(v) => write!(f, "Float({})", v),
             DataType::Int(n) => write!(f, "Int({})", n),
             DataType::UnsignedInt(n) => write!(f, "UnsignedInt({})", n),
             DataType::BigInt(n) => write!(f, "BigInt({})", n),
@@ -100,6 +160,8 @@ impl DataType {
     pub fn deep_clone(&self) -> Self {
         match *self {
             DataType::Text(ref cstr) => DataType::Text(ArcCStr::from(&**cstr)),
+            DataType::Bytes(ref b) => DataType::Bytes(Arc::new(Vec::clone(b))),
+            DataType::Uuid(ref u) => DataType::Uuid(Arc::new(**u)),
             ref dt => dt.clone(),
         }
     }
@@ -144,6 +206,340 @@ impl DataType {
             _ => false,
         }
     }
+
+    /// Checks if this value is of a decimal data type.
+    pub fn is_decimal(&self) -> bool {
+        match *self {
+            DataType::Decimal(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks if this value is of a binary data type (i.e., can be converted into `&[u8]`).
+    pub fn is_bytes(&self) -> bool {
+        match *self {
+            DataType::Bytes(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks if this value is of a floating point data type.
+    pub fn is_float(&self) -> bool {
+        match *self {
+            DataType::Float(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks if this value is of a boolean data type.
+    pub fn is_bool(&self) -> bool {
+        match *self {
+            DataType::Bool(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks if this value is of a UUID data type.
+    pub fn is_uuid(&self) -> bool {
+        match *self {
+            DataType::Uuid(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Parses a UUID in its textual form, either hyphenated (`67e55044-10b1-426f-9247-bb680e5fe0c8`)
+    /// or simple (`67e5504410b1426f9247bb680e5fe0c8`).
+    pub fn parse_uuid(s: &str) -> Result<Self, &'static str> {
+        let s = s.as_bytes();
+        let hyphenated = match s.len() {
+            36 => true,
+            32 => false,
+            _ => return Err("UUID must be 32 hex digits, optionally hyphenated"),
+        };
+
+        let mut digits = s.iter().enumerate().filter_map(|(i, &c)| {
+            if hyphenated && (i == 8 || i == 13 || i == 18 || i == 23) {
+                if c == b'-' {
+                    None
+                } else {
+                    Some(None)
+                }
+            } else {
+                Some((c as char).to_digit(16))
+            }
+        });
+
+        let mut bytes = [0u8; 16];
+        for b in bytes.iter_mut() {
+            let hi = digits.next().and_then(|d| d);
+            let lo = digits.next().and_then(|d| d);
+            match (hi, lo) {
+                (Some(hi), Some(lo)) => *b = (hi * 16 + lo) as u8,
+                _ => return Err("invalid character in UUID"),
+            }
+        }
+        Ok(DataType::Uuid(Arc::new(bytes)))
+    }
+
+    /// Construct a decimal from its unscaled value and the number of digits to the right of the
+    /// decimal point.
+    ///
+    /// Values with a scale greater than `MAX_DECIMAL_SCALE` are rounded (half away from zero).
+    /// Fails if the value does not fit in a `DataType::Decimal`.
+    pub fn decimal(mut unscaled: i128, mut scale: u8) -> Result<Self, &'static str> {
+        if scale > MAX_DECIMAL_SCALE {
+            unscaled = div_round(unscaled, pow10(scale - MAX_DECIMAL_SCALE));
+            scale = MAX_DECIMAL_SCALE;
+        }
+        let m = i64::try_from(unscaled).map_err(|_| "decimal out of range")?;
+        Ok(DataType::Decimal(m, scale))
+    }
+
+    /// Parse a decimal from its textual representation (e.g., `"-12.50"`).
+    pub fn parse_decimal(s: &str) -> Result<Self, &'static str> {
+        let s = s.trim();
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (int, frac) = match digits.find('.') {
+            Some(i) => (&digits[..i], &digits[i + 1..]),
+            None => (digits, ""),
+        };
+        if int.is_empty() && frac.is_empty() {
+            return Err("empty decimal");
+        }
+        if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err("invalid decimal");
+        }
+        if frac.len() > usize::from(MAX_DECIMAL_SCALE) {
+            return Err("too many decimal digits");
+        }
+
+        let mut unscaled: i128 = 0;
+        for b in int.bytes().chain(frac.bytes()) {
+            unscaled = unscaled
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(i128::from(b - b'0')))
+                .ok_or("decimal out of range")?;
+        }
+        if negative {
+            unscaled = -unscaled;
+        }
+        let m = i64::try_from(unscaled).map_err(|_| "decimal out of range")?;
+        Ok(DataType::Decimal(m, frac.len() as u8))
+    }
+
+    /// Parse a value of the given SQL type from its textual representation (e.g., `"007"` as an
+    /// `INT` is 7, while as a `VARCHAR` it stays `"007"`).
+    ///
+    /// Values of types that have no textual representation of their own are taken to be text.
+    pub fn parse_as(s: &str, sql_type: &SqlType) -> Result<Self, String> {
+        let e = |e: &dyn fmt::Display| e.to_string();
+        match *sql_type {
+            SqlType::Int(_) => s.parse::<i32>().map(Into::into).map_err(|x| e(&x)),
+            SqlType::UnsignedInt(_) => s.parse::<u32>().map(Into::into).map_err(|x| e(&x)),
+            SqlType::Bigint(_) => s.parse::<i64>().map(Into::into).map_err(|x| e(&x)),
+            SqlType::UnsignedBigint(_) => s.parse::<u64>().map(Into::into).map_err(|x| e(&x)),
+            SqlType::Double | SqlType::Float | SqlType::Real => {
+                s.parse::<f64>().map(Into::into).map_err(|x| e(&x))
+            }
+            SqlType::Decimal(_, scale) => DataType::parse_decimal(s)
+                .and_then(|d| d.rescale_decimal(scale))
+                .map_err(|x| e(&x)),
+            SqlType::Bool => match s {
+                "1" | "true" | "TRUE" => Ok(DataType::Bool(true)),
+                "0" | "false" | "FALSE" => Ok(DataType::Bool(false)),
+                _ => Err(e(&"not a boolean")),
+            },
+            SqlType::Timestamp => NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                .map(Into::into)
+                .map_err(|x| e(&x)),
+            SqlType::Binary(16) => DataType::parse_uuid(s).map_err(|x| e(&x)),
+            _ => Ok(s.into()),
+        }
+    }
+
+    /// Convert a decimal to the given scale, rounding (half away from zero) if digits have to be
+    /// dropped. Fails if the value is not a decimal, or does not fit at the new scale.
+    pub fn rescale_decimal(&self, scale: u8) -> Result<Self, &'static str> {
+        let (m, from) = match *self {
+            DataType::Decimal(m, from) => (i128::from(m), from),
+            _ => return Err("not a decimal"),
+        };
+        let scale = std::cmp::min(scale, MAX_DECIMAL_SCALE);
+        let unscaled = if scale >= from {
+            m * pow10(scale - from)
+        } else {
+            div_round(m, pow10(from - scale))
+        };
+        let m = i64::try_from(unscaled).map_err(|_| "decimal out of range")?;
+        Ok(DataType::Decimal(m, scale))
+    }
+
+    /// Returns this value as an `f64` if it is numeric.
+    pub fn to_f64(&self) -> Option<f64> {
+        match *self {
+            DataType::Real(i, f) => Some(i as f64 + f64::from(f) / FLOAT_PRECISION),
+            DataType::Decimal(m, scale) => Some(m as f64 / 10f64.powi(i32::from(scale))),
+            DataType::Float(f) => Some(f),
+            DataType::Int(i) => Some(f64::from(i)),
+            DataType::UnsignedInt(i) => Some(f64::from(i)),
+            DataType::BigInt(i) => Some(i as f64),
+            DataType::UnsignedBigInt(i) => Some(i as f64),
+            _ => None,
+        }
+    }
+
+    /// Construct a timestamp from the number of seconds since the UNIX epoch.
+    ///
+    /// Returns `None` if the timestamp is out of range.
+    pub fn from_epoch_secs(secs: i64) -> Option<Self> {
+        NaiveDateTime::from_timestamp_opt(secs, 0).map(DataType::Timestamp)
+    }
+
+    /// Construct a timestamp from the number of milliseconds since the UNIX epoch.
+    ///
+    /// Returns `None` if the timestamp is out of range.
+    pub fn from_epoch_millis(millis: i64) -> Option<Self> {
+        NaiveDateTime::from_timestamp_opt(
+            millis.div_euclid(1_000),
+            (millis.rem_euclid(1_000) * 1_000_000) as u32,
+        )
+        .map(DataType::Timestamp)
+    }
+
+    /// Returns the number of seconds since the UNIX epoch if this value is a timestamp.
+    pub fn epoch_secs(&self) -> Option<i64> {
+        match *self {
+            DataType::Timestamp(ts) => Some(ts.timestamp()),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of milliseconds since the UNIX epoch if this value is a timestamp.
+    pub fn epoch_millis(&self) -> Option<i64> {
+        match *self {
+            DataType::Timestamp(ts) => Some(ts.timestamp_millis()),
+            _ => None,
+        }
+    }
+}
+
+fn pow10(exp: u8) -> i128 {
+    10i128.pow(u32::from(exp))
+}
+
+/// Divides `n` by `d`, rounding half away from zero.
+fn div_round(n: i128, d: i128) -> i128 {
+    let q = n / d;
+    let r = n % d;
+    if 2 * r.abs() >= d.abs() {
+        if (n < 0) == (d < 0) {
+            q + 1
+        } else {
+            q - 1
+        }
+    } else {
+        q
+    }
+}
+
+/// Returns the unscaled value and scale of values that can take part in exact decimal arithmetic.
+fn decimal_parts(d: &DataType) -> Option<(i128, u8)> {
+    match *d {
+        DataType::Decimal(m, scale) => Some((i128::from(m), scale)),
+        DataType::Real(i, f) => Some((i128::from(i) * pow10(9) + i128::from(f), 9)),
+        DataType::Int(..)
+        | DataType::UnsignedInt(..)
+        | DataType::BigInt(..)
+        | DataType::UnsignedBigInt(..) => Some((i128::from(d), 0)),
+        _ => None,
+    }
+}
+
+/// The position of a value's kind among all kinds, in the order Ints (and Decimals), Reals,
+/// Floats, Text, Timestamps, Bytes, Uuids, Bools, None.
+fn kind_rank(d: &DataType) -> u8 {
+    match *d {
+        DataType::Int(..)
+        | DataType::UnsignedInt(..)
+        | DataType::BigInt(..)
+        | DataType::UnsignedBigInt(..)
+        | DataType::Decimal(..) => 0,
+        DataType::Real(..) => 1,
+        DataType::Float(..) => 2,
+        DataType::Text(..) | DataType::TinyText(..) => 3,
+        DataType::Timestamp(..) => 4,
+        DataType::Bytes(..) => 5,
+        DataType::Uuid(..) => 6,
+        DataType::Bool(..) => 7,
+        DataType::None => 8,
+    }
+}
+
+/// Maps a float onto an integer with the same (total) order, where all NaNs are equal and greater
+/// than every other value, and `-0.0` is equal to `0.0`.
+pub(crate) fn float_key(f: f64) -> i64 {
+    if f.is_nan() {
+        return std::i64::MAX;
+    }
+    // this also turns -0.0 into 0.0
+    let f = if f == 0.0 { 0.0 } else { f };
+    let bits = f.to_bits() as i64;
+    if bits < 0 {
+        // negative floats order inversely to their bit patterns
+        bits ^ std::i64::MAX
+    } else {
+        bits
+    }
+}
+
+/// Brings the unscaled values of two decimals to a common scale.
+fn align_decimals((a, sa): (i128, u8), (b, sb): (i128, u8)) -> (i128, i128, u8) {
+    use std::cmp::max;
+    let scale = max(sa, sb);
+    (a * pow10(scale - sa), b * pow10(scale - sb), scale)
+}
+
+/// Removes trailing zeros after the decimal point.
+fn normalize_decimal(mut m: i64, mut scale: u8) -> (i64, u8) {
+    while scale > 0 && m % 10 == 0 {
+        m /= 10;
+        scale -= 1;
+    }
+    (m, scale)
+}
+
+/// Performs exact arithmetic where at least one operand is a decimal.
+///
+/// Like division by zero, results that don't fit in a decimal are NULL.
+fn decimal_operation(op: &str, first: &DataType, second: &DataType) -> DataType {
+    let (a, b) = match (decimal_parts(first), decimal_parts(second)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => panic!("can't {} a {:?} and {:?}", op, first, second),
+    };
+
+    match op {
+        "+" | "-" => {
+            let (a, b, scale) = align_decimals(a, b);
+            DataType::decimal(if op == "+" { a + b } else { a - b }, scale)
+                .unwrap_or(DataType::None)
+        }
+        "*" => DataType::decimal(a.0 * b.0, a.1 + b.1).unwrap_or(DataType::None),
+        "/" => {
+            if b.0 == 0 {
+                return DataType::None;
+            }
+            // result = (a / 10^sa) / (b / 10^sb), computed with `scale` fractional digits
+            let scale = std::cmp::min(a.1 + DECIMAL_DIV_INCREMENT, MAX_DECIMAL_SCALE);
+            a.0.checked_mul(pow10(scale + b.1 - a.1))
+                .and_then(|n| DataType::decimal(div_round(n, b.0), scale).ok())
+                .unwrap_or(DataType::None)
+        }
+        _ => unreachable!(),
+    }
 }
 
 impl PartialEq for DataType {
@@ -189,8 +585,25 @@ impl PartialEq for DataType {
                 let b: i128 = other.into();
                 a == b
             }
+            (&DataType::Decimal(..), &DataType::Decimal(..))
+            | (&DataType::Decimal(..), &DataType::Int(..))
+            | (&DataType::Decimal(..), &DataType::UnsignedInt(..))
+            | (&DataType::Decimal(..), &DataType::BigInt(..))
+            | (&DataType::Decimal(..), &DataType::UnsignedBigInt(..))
+            | (&DataType::Int(..), &DataType::Decimal(..))
+            | (&DataType::UnsignedInt(..), &DataType::Decimal(..))
+            | (&DataType::BigInt(..), &DataType::Decimal(..))
+            | (&DataType::UnsignedBigInt(..), &DataType::Decimal(..)) => {
+                let (a, b, _) =
+                    align_decimals(decimal_parts(self).unwrap(), decimal_parts(other).unwrap());
+                a == b
+            }
             (&DataType::Real(ai, af), &DataType::Real(bi, bf)) => ai == bi && af == bf,
             (&DataType::Timestamp(tsa), &DataType::Timestamp(tsb)) => tsa == tsb,
+            (&DataType::Bytes(ref a), &DataType::Bytes(ref b)) => a == b,
+            (&DataType::Uuid(ref a), &DataType::Uuid(ref b)) => a == b,
+            (&DataType::Bool(a), &DataType::Bool(b)) => a == b,
+            (&DataType::Float(a), &DataType::Float(b)) => float_key(a) == float_key(b),
             (&DataType::None, &DataType::None) => true,
 
             _ => false,
@@ -198,6 +611,9 @@ impl PartialEq for DataType {
     }
 }
 
+// `Float`s are totally ordered (see `float_key`), so this is sound.
+impl Eq for DataType {}
+
 use std::cmp::Ordering;
 impl PartialOrd for DataType {
     fn partial_cmp(&self, other: &DataType) -> Option<Ordering> {
@@ -236,21 +652,49 @@ impl Ord for DataType {
                 let b: i128 = other.into();
                 a.cmp(&b)
             }
+            (&DataType::Decimal(..), &DataType::Decimal(..))
+            | (&DataType::Decimal(..), &DataType::Int(..))
+            | (&DataType::Decimal(..), &DataType::UnsignedInt(..))
+            | (&DataType::Decimal(..), &DataType::BigInt(..))
+            | (&DataType::Decimal(..), &DataType::UnsignedBigInt(..))
+            | (&DataType::Int(..), &DataType::Decimal(..))
+            | (&DataType::UnsignedInt(..), &DataType::Decimal(..))
+            | (&DataType::BigInt(..), &DataType::Decimal(..))
+            | (&DataType::UnsignedBigInt(..), &DataType::Decimal(..)) => {
+                let (a, b, _) =
+                    align_decimals(decimal_parts(self).unwrap(), decimal_parts(other).unwrap());
+                a.cmp(&b)
+            }
             (&DataType::Real(ai, af), &DataType::Real(ref bi, ref bf)) => {
                 ai.cmp(bi).then_with(|| af.cmp(bf))
             }
             (&DataType::Timestamp(tsa), &DataType::Timestamp(ref tsb)) => tsa.cmp(tsb),
+            (&DataType::Bytes(ref a), &DataType::Bytes(ref b)) => a.cmp(b),
+            (&DataType::Uuid(ref a), &DataType::Uuid(ref b)) => a.cmp(b),
+            (&DataType::Bool(a), &DataType::Bool(ref b)) => a.cmp(b),
+            (&DataType::Float(a), &DataType::Float(b)) => float_key(a).cmp(&float_key(b)),
             (&DataType::None, &DataType::None) => Ordering::Equal,
 
-            // order Ints, Reals, Text, Timestamps, None
-            (&DataType::Int(..), _)
-            | (&DataType::UnsignedInt(..), _)
-            | (&DataType::BigInt(..), _)
-            | (&DataType::UnsignedBigInt(..), _) => Ordering::Greater,
-            (&DataType::Real(..), _) => Ordering::Greater,
-            (&DataType::Text(..), _) | (&DataType::TinyText(..), _) => Ordering::Greater,
-            (&DataType::Timestamp(..), _) => Ordering::Greater,
-            (&DataType::None, _) => Ordering::Greater,
+            // numbers of different kinds compare by value, exactly unless one of them is a float.
+            // they are never equal as `DataType`s though, so ties are broken by kind.
+            (&DataType::Real(..), _)
+            | (_, &DataType::Real(..))
+            | (&DataType::Float(..), _)
+            | (_, &DataType::Float(..))
+                if self.to_f64().is_some() && other.to_f64().is_some() =>
+            {
+                let by_value = match (decimal_parts(self), decimal_parts(other)) {
+                    (Some(a), Some(b)) => {
+                        let (a, b, _) = align_decimals(a, b);
+                        a.cmp(&b)
+                    }
+                    _ => float_key(self.to_f64().unwrap()).cmp(&float_key(other.to_f64().unwrap())),
+                };
+                by_value.then_with(|| kind_rank(self).cmp(&kind_rank(other)))
+            }
+
+            // values of unrelated kinds are ordered by kind
+            _ => kind_rank(self).cmp(&kind_rank(other)),
         }
     }
 }
@@ -279,6 +723,20 @@ impl Hash for DataType {
                 t.hash(state)
             }
             DataType::Timestamp(ts) => ts.hash(state),
+            DataType::Bytes(ref b) => b.hash(state),
+            DataType::Uuid(ref u) => u128::from_be_bytes(**u).hash(state),
+            DataType::Bool(b) => b.hash(state),
+            DataType::Float(f) => float_key(f).hash(state),
+            DataType::Decimal(m, scale) => {
+                // decimals must hash the same as the equal integral values
+                match normalize_decimal(m, scale) {
+                    (m, 0) => m.hash(state),
+                    (m, scale) => {
+                        m.hash(state);
+                        scale.hash(state);
+                    }
+                }
+            }
         }
     }
 }
@@ -373,6 +831,7 @@ impl<'a> From<&'a Literal> for DataType {
             Literal::Null => DataType::None,
             Literal::Integer(i) => (i as i64).into(),
             Literal::String(ref s) => s.as_str().into(),
+            Literal::Blob(ref b) => b.clone().into(),
             Literal::CurrentTimestamp => {
                 let ts = chrono::Local::now().naive_local();
                 DataType::Timestamp(ts)
@@ -397,6 +856,93 @@ impl From<NaiveDateTime> for DataType {
     }
 }
 
+impl<Tz: TimeZone> From<DateTime<Tz>> for DataType {
+    fn from(dt: DateTime<Tz>) -> Self {
+        DataType::Timestamp(dt.naive_utc())
+    }
+}
+
+impl From<DataType> for NaiveDateTime {
+    fn from(data: DataType) -> Self {
+        (&data).into()
+    }
+}
+
+impl From<&'_ DataType> for NaiveDateTime {
+    fn from(data: &'_ DataType) -> Self {
+        if let DataType::Timestamp(ts) = *data {
+            ts
+        } else {
+            panic!("attempted to convert a {:?} to a NaiveDateTime", data)
+        }
+    }
+}
+
+impl From<&'_ DataType> for DateTime<Utc> {
+    fn from(data: &'_ DataType) -> Self {
+        Utc.from_utc_datetime(&NaiveDateTime::from(data))
+    }
+}
+
+impl From<Vec<u8>> for DataType {
+    fn from(b: Vec<u8>) -> Self {
+        DataType::Bytes(Arc::new(b))
+    }
+}
+
+impl From<Arc<Vec<u8>>> for DataType {
+    fn from(b: Arc<Vec<u8>>) -> Self {
+        DataType::Bytes(b)
+    }
+}
+
+impl From<bool> for DataType {
+    fn from(b: bool) -> Self {
+        DataType::Bool(b)
+    }
+}
+
+impl From<DataType> for bool {
+    fn from(data: DataType) -> Self {
+        (&data).into()
+    }
+}
+
+impl From<&'_ DataType> for bool {
+    fn from(data: &'_ DataType) -> Self {
+        if let DataType::Bool(b) = *data {
+            b
+        } else {
+            panic!("attempted to convert a {:?} to a bool", data)
+        }
+    }
+}
+
+impl From<[u8; 16]> for DataType {
+    fn from(u: [u8; 16]) -> Self {
+        DataType::Uuid(Arc::new(u))
+    }
+}
+
+impl From<&'_ DataType> for [u8; 16] {
+    fn from(data: &'_ DataType) -> Self {
+        if let DataType::Uuid(ref u) = *data {
+            **u
+        } else {
+            panic!("attempted to convert a {:?} to a UUID", data)
+        }
+    }
+}
+
+impl<'a> From<&'a DataType> for &'a [u8] {
+    fn from(data: &'a DataType) -> Self {
+        match *data {
+            DataType::Bytes(ref b) => &b[..],
+            _ => panic!("attempted to convert a {:?} to bytes", data),
+        }
+    }
+}
+
 // This conversion has many unwraps, but all of them are expected to be safe,
 // because DataType variants (i.e. `Text` and `TinyText`) constructors are all
 // generated from valid UTF-8 strings, or the constructor fails (e.g. TryFrom &[u8]).
@@ -515,12 +1061,8 @@ impl From<DataType> for f64 {
 
 impl From<&'_ DataType> for f64 {
     fn from(data: &'_ DataType) -> Self {
-        match *data {
-            DataType::Real(i, f) => i as f64 + f64::from(f) / FLOAT_PRECISION,
-            DataType::Int(i) => f64::from(i),
-            DataType::BigInt(i) => i as f64,
-            _ => panic!("attempted to convert a {:?} to an f64", data),
-        }
+        data.to_f64()
+            .unwrap_or_else(|| panic!("attempted to convert a {:?} to an f64", data))
     }
 }
 
@@ -592,6 +1134,14 @@ macro_rules! arithmetic_operation (
     ($op:tt, $first:ident, $second:ident) => (
         match ($first, $second) {
             (&DataType::None, _) | (_, &DataType::None) => DataType::None,
+            (first, second) if first.is_float() || second.is_float() => {
+                let a: f64 = first.into();
+                let b: f64 = second.into();
+                DataType::Float(a $op b)
+            }
+            (first, second) if first.is_decimal() || second.is_decimal() => {
+                decimal_operation(stringify!($op), first, second)
+            }
             (&DataType::Int(a), &DataType::Int(b)) => (a $op b).into(),
             (&DataType::UnsignedInt(a), &DataType::UnsignedInt(b)) => (a $op b).into(),
             (&DataType::BigInt(a), &DataType::BigInt(b)) => (a $op b).into(),
@@ -805,6 +1355,34 @@ mod tests {
         assert!(a_dt.is_err());
     }
 
+    #[test]
+    fn timestamp_epoch_conversions() {
+        let ts = DataType::from_epoch_secs(1_500_000_000).unwrap();
+        assert!(ts.is_datetime());
+        assert_eq!(ts.epoch_secs(), Some(1_500_000_000));
+        assert_eq!(ts.epoch_millis(), Some(1_500_000_000_000));
+
+        let ts = DataType::from_epoch_millis(-1_500).unwrap();
+        assert_eq!(ts.epoch_millis(), Some(-1_500));
+        assert_eq!(ts.epoch_secs(), Some(-2));
+
+        assert_eq!(DataType::BigInt(5).epoch_secs(), None);
+
+        let dt = Utc.timestamp(42, 0);
+        let ts = DataType::from(dt);
+        assert_eq!(Some(ts.clone()), DataType::from_epoch_secs(42));
+        assert_eq!(DateTime::<Utc>::from(&ts), dt);
+        assert_eq!(NaiveDateTime::from(ts), dt.naive_utc());
+
+        assert!(DataType::from_epoch_secs(1) < DataType::from_epoch_secs(2));
+        assert!(DataType::from_epoch_millis(1_001) > DataType::from_epoch_secs(1));
+
+        // out-of-range timestamps are rejected rather than panicking
+        assert_eq!(DataType::from_epoch_secs(std::i64::MAX), None);
+        assert_eq!(DataType::from_epoch_secs(std::i64::MIN), None);
+        assert_eq!(DataType::from_epoch_millis(std::i64::MAX), None);
+    }
+
     #[test]
     fn real_to_string() {
         let a: DataType = (2.5).into();
@@ -868,6 +1446,261 @@ mod tests {
         assert_eq!(&DataType::BigInt(4) / &DataType::from(2), 2.into());
     }
 
+    #[test]
+    fn decimal_arithmetic() {
+        let a = DataType::parse_decimal("10.25").unwrap();
+        let b = DataType::parse_decimal("0.1").unwrap();
+        assert_eq!(a, DataType::Decimal(1025, 2));
+        assert_eq!(&a + &b, DataType::Decimal(1035, 2));
+        assert_eq!(&a - &b, DataType::Decimal(1015, 2));
+        assert_eq!(&a * &b, DataType::Decimal(1025, 3));
+        assert_eq!(&a / &DataType::from(4), DataType::Decimal(25625, 4));
+        assert_eq!(&a / &DataType::from(0), DataType::None);
+
+        // results that don't fit are NULL rather than a panic
+        let big = DataType::Decimal(i64::max_value(), 0);
+        assert_eq!(&big + &b, DataType::None);
+        assert_eq!(&big * &a, DataType::None);
+        assert_eq!(&big / &b, DataType::None);
+        assert_eq!(
+            DataType::decimal(i128::from(i64::max_value()) + 1, 0),
+            Err("decimal out of range")
+        );
+        assert_eq!(
+            DataType::decimal(12345, MAX_DECIMAL_SCALE + 2),
+            Ok(DataType::Decimal(123, MAX_DECIMAL_SCALE))
+        );
+
+        // no floating point error creeps in
+        let mut sum = DataType::Decimal(0, 2);
+        for _ in 0..10 {
+            sum = &sum + &b;
+        }
+        assert_eq!(sum, DataType::from(1));
+        assert_eq!(sum.to_string(), "1.00");
+
+        // mixing with reals stays exact
+        assert_eq!(
+            &b + &DataType::from(0.2),
+            DataType::parse_decimal("0.3").unwrap()
+        );
+    }
+
+    #[test]
+    fn decimal_equality() {
+        let hash = |dt: &DataType| {
+            use std::collections::hash_map::DefaultHasher;
+            use std::hash::{Hash, Hasher};
+            let mut s = DefaultHasher::new();
+            dt.hash(&mut s);
+            s.finish()
+        };
+
+        let a = DataType::Decimal(150, 2);
+        let b = DataType::Decimal(15, 1);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(DataType::Decimal(200, 2), DataType::Int(2));
+        assert_eq!(hash(&DataType::Decimal(200, 2)), hash(&DataType::Int(2)));
+        assert!(DataType::Decimal(-1, 2) < DataType::Int(0));
+        assert!(DataType::Decimal(101, 2) > DataType::BigInt(1));
+        assert_ne!(a, DataType::from(1.5));
+
+        assert_eq!(DataType::Decimal(-5, 2).to_string(), "-0.05");
+        assert_eq!(
+            format!("{:?}", DataType::Decimal(1234, 1)),
+            "Decimal(123.4)"
+        );
+        assert!(DataType::parse_decimal("1.2.3").is_err());
+        assert!(DataType::parse_decimal("-").is_err());
+        assert_eq!(
+            DataType::parse_decimal("-.5").unwrap(),
+            DataType::Decimal(-5, 1)
+        );
+    }
+
+    #[test]
+    fn decimal_rescaling() {
+        let d = DataType::parse_decimal("12.345").unwrap();
+        assert_eq!(d.rescale_decimal(5).unwrap(), DataType::Decimal(1234500, 5));
+        assert_eq!(d.rescale_decimal(2).unwrap(), DataType::Decimal(1235, 2));
+        assert_eq!(d.rescale_decimal(0).unwrap(), DataType::Decimal(12, 0));
+        assert_eq!(
+            DataType::Decimal(-125, 2).rescale_decimal(1).unwrap(),
+            DataType::Decimal(-13, 1)
+        );
+        assert!(DataType::Decimal(i64::max_value(), 0)
+            .rescale_decimal(1)
+            .is_err());
+        assert!(DataType::Int(1).rescale_decimal(1).is_err());
+    }
+
+    #[test]
+    fn parse_as_sql_types() {
+        let parse = |s, t| DataType::parse_as(s, &t);
+        assert_eq!(parse("007", SqlType::Int(32)).unwrap(), DataType::Int(7));
+        assert_eq!(
+            parse("007", SqlType::Varchar(255)).unwrap(),
+            DataType::from("007")
+        );
+        assert_eq!(
+            parse("-3", SqlType::Bigint(64)).unwrap(),
+            DataType::BigInt(-3)
+        );
+        assert_eq!(
+            parse("1.5", SqlType::Decimal(10, 2)).unwrap(),
+            DataType::Decimal(150, 2)
+        );
+        assert_eq!(parse("true", SqlType::Bool).unwrap(), DataType::Bool(true));
+        assert!(parse("7x", SqlType::Int(32)).is_err());
+        assert!(parse("-1", SqlType::UnsignedInt(32)).is_err());
+        assert!(parse("yes", SqlType::Bool).is_err());
+    }
+
+    #[test]
+    fn float_total_order() {
+        let hash = |dt: &DataType| {
+            use std::collections::hash_map::DefaultHasher;
+            use std::hash::{Hash, Hasher};
+            let mut s = DefaultHasher::new();
+            dt.hash(&mut s);
+            s.finish()
+        };
+
+        let nan = DataType::Float(std::f64::NAN);
+        let other_nan = DataType::Float(-std::f64::NAN);
+        assert_eq!(nan, other_nan);
+        assert_eq!(hash(&nan), hash(&other_nan));
+        assert!(nan > DataType::Float(std::f64::INFINITY));
+
+        let zero = DataType::Float(0.0);
+        let neg_zero = DataType::Float(-0.0);
+        assert_eq!(zero, neg_zero);
+        assert_eq!(hash(&zero), hash(&neg_zero));
+
+        let mut fs: Vec<DataType> =
+            vec![2.5, -1.0, std::f64::NAN, -2.5, 0.0, std::f64::NEG_INFINITY]
+                .into_iter()
+                .map(DataType::Float)
+                .collect();
+        fs.sort();
+        assert_eq!(
+            fs,
+            vec![std::f64::NEG_INFINITY, -2.5, -1.0, 0.0, 2.5, std::f64::NAN]
+                .into_iter()
+                .map(DataType::Float)
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            &DataType::Float(1.5) + &DataType::Int(1),
+            DataType::Float(2.5)
+        );
+        assert!((&DataType::Float(0.0) / &DataType::Float(0.0)).is_float());
+        assert_eq!(format!("{:?}", DataType::Float(1.5)), "Float(1.5)");
+    }
+
+    #[test]
+    fn mixed_kind_order() {
+        use std::cmp::Ordering;
+
+        let values = vec![
+            DataType::None,
+            DataType::from(true),
+            DataType::Float(-1.5),
+            DataType::Float(2.0),
+            DataType::Float(std::f64::NAN),
+            DataType::Real(2, 0),
+            DataType::Real(-1, -500_000_000),
+            DataType::Decimal(-15, 1),
+            DataType::Decimal(25, 1),
+            DataType::Int(2),
+            DataType::BigInt(3),
+            DataType::from("a"),
+            DataType::from_epoch_secs(0).unwrap(),
+        ];
+        for a in &values {
+            for b in &values {
+                assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{:?} vs {:?}", a, b);
+                assert_eq!(a.cmp(b) == Ordering::Equal, a == b, "{:?} vs {:?}", a, b);
+                for c in &values {
+                    if a < b && b < c {
+                        assert!(a < c, "{:?} < {:?} < {:?}", a, b, c);
+                    }
+                }
+            }
+        }
+
+        // numbers compare by value across kinds, with ties broken by kind
+        assert!(DataType::Decimal(25, 1) > DataType::Real(2, 0));
+        assert!(DataType::Real(2, 0) > DataType::Int(2));
+        assert!(DataType::Float(2.0) > DataType::Real(2, 0));
+        assert!(DataType::Float(-1.5) < DataType::Int(2));
+        assert!(DataType::Float(std::f64::NAN) > DataType::BigInt(3));
+    }
+
+    #[test]
+    fn bool_conversions() {
+        let t = DataType::from(true);
+        let f = DataType::from(false);
+        assert!(t.is_bool());
+        assert!(bool::from(&t));
+        assert!(!bool::from(f.clone()));
+        assert!(f < t);
+        assert_ne!(t, DataType::from(1));
+        assert_eq!(t.to_string(), "true");
+        assert_eq!(format!("{:?}", f), "Bool(false)");
+    }
+
+    #[test]
+    fn uuid_conversions() {
+        let text = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let u = DataType::parse_uuid(text).unwrap();
+        assert!(u.is_uuid());
+        assert_eq!(u.to_string(), text);
+        assert_eq!(format!("{:?}", u), format!("Uuid({})", text));
+        assert_eq!(
+            DataType::parse_uuid("67E5504410B1426F9247BB680E5FE0C8").unwrap(),
+            u
+        );
+
+        let bytes: [u8; 16] = (&u).into();
+        assert_eq!(bytes[0], 0x67);
+        assert_eq!(bytes[15], 0xc8);
+        assert_eq!(DataType::from(bytes), u);
+        assert!(DataType::from([0; 16]) < u);
+
+        assert!(DataType::parse_uuid("67e55044-10b1-426f-9247-bb680e5fe0c").is_err());
+        assert!(DataType::parse_uuid("67e55044x10b1-426f-9247-bb680e5fe0c8").is_err());
+        assert!(DataType::parse_uuid("g7e5504410b1426f9247bb680e5fe0c8").is_err());
+    }
+
+    #[test]
+    fn bytes_conversions() {
+        let b = DataType::from(vec![0xde, 0xad, 0x00, 0x01]);
+        assert!(b.is_bytes());
+        let bts: &[u8] = (&b).into();
+        assert_eq!(bts, &[0xde, 0xad, 0x00, 0x01]);
+
+        // clones share the underlying buffer, deep clones do not
+        let c = b.clone();
+        let d = b.deep_clone();
+        match (&b, &c, &d) {
+            (DataType::Bytes(b), DataType::Bytes(c), DataType::Bytes(d)) => {
+                assert!(Arc::ptr_eq(b, c));
+                assert!(!Arc::ptr_eq(b, d));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(b, d);
+
+        assert_ne!(b, DataType::from(vec![0xde, 0xad]));
+        assert!(DataType::from(vec![0x01]) < DataType::from(vec![0x01, 0x00]));
+        assert_ne!(DataType::from(vec![b'h', b'i']), DataType::from("hi"));
+        assert_eq!(b.to_string(), "0xdead0001");
+        assert_eq!(format!("{:?}", b), "Bytes(0xdead0001)");
+    }
+
     #[test]
     #[should_panic(expected = "can't + a TinyText(\"hi\") and Int(5)")]
     fn add_invalid_types() {
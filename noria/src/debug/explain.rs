@@ -0,0 +1,66 @@
+use crate::MaterializationStatus;
+use petgraph::graph::NodeIndex;
+use std::fmt;
+
+/// An operator that contributes to a view, along with the operators it reads from.
+///
+/// Ingress, egress, and sharding nodes are left out, so `parents` are the closest upstream
+/// operators (or base tables).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Plan {
+    /// The operator's node in the data-flow graph.
+    pub node: NodeIndex,
+    /// The operator's name.
+    pub name: String,
+    /// A textual description of the operator.
+    pub description: String,
+    /// The domain the operator runs in.
+    pub domain: usize,
+    /// How the operator's state is materialized.
+    pub materialized: MaterializationStatus,
+    /// The columns of each index on the operator's state.
+    pub indices: Vec<Vec<usize>>,
+    /// Total memory size of the operator's state across all shards, in bytes.
+    pub mem_size: u64,
+    /// The operators this operator reads from.
+    pub parents: Vec<Plan>,
+}
+
+impl Plan {
+    fn fmt_at(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let materialized = match self.materialized {
+            MaterializationStatus::Not => "not materialized",
+            MaterializationStatus::Full => "full",
+            MaterializationStatus::Partial { .. } => "partial",
+        };
+        write!(
+            f,
+            "{:indent$}{} [n{}, domain {}]: {}, {}",
+            "",
+            self.name,
+            self.node.index(),
+            self.domain,
+            self.description,
+            materialized,
+            indent = 2 * depth,
+        )?;
+        if !self.indices.is_empty() {
+            write!(f, ", indexed on {:?}", self.indices)?;
+        }
+        if self.mem_size != 0 {
+            write!(f, ", {} bytes", self.mem_size)?;
+        }
+        writeln!(f)?;
+
+        for p in &self.parents {
+            p.fmt_at(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_at(f, 0)
+    }
+}
@@ -1,2 +1,6 @@
+/// Types for describing how views are computed.
+pub mod explain;
 /// Types related to graph statistics.
 pub mod stats;
+/// Types for following individual writes through the graph.
+pub mod trace;
@@ -21,6 +21,8 @@ pub struct DomainStats {
     pub total_forward_time: u64,
     /// Total wall-clock time spent waiting for work in this domain.
     pub wait_time: u64,
+    /// Number of packets waiting to be sent to other domains when the statistics were collected.
+    pub queue_depth: u64,
 }
 
 /// Statistics about a node.
@@ -34,12 +36,38 @@ pub struct NodeStats {
     pub process_time: u64,
     /// Total thread time elapsed while processing in this node.
     pub process_ptime: u64,
-    /// Total memory size of this node's state.
+    /// Total number of records this node has processed during regular (non-replay) forwarding.
+    pub records: u64,
+    /// Number of rows in this node's state.
+    ///
+    /// This is always zero for readers and for nodes without materialized state.
+    pub rows: usize,
+    /// Total memory size of this node's state, including its indices.
     pub mem_size: u64,
     /// The materialization type of this node's state.
     pub materialized: MaterializationStatus,
     /// The value returned from Ingredient::probe.
     pub probe_result: HashMap<String, String>,
+    /// The latencies of reads served by this node, if it is a reader that has served any.
+    pub read_latency: Option<ReadLatency>,
+}
+
+/// A summary of the latencies of the reads served by a reader.
+///
+/// All latencies are in microseconds, and include the time spent waiting for the lookup lock,
+/// performing the lookup, and copying out the results.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadLatency {
+    /// The number of reads.
+    pub count: u64,
+    /// The median read latency.
+    pub p50: u64,
+    /// The 90th percentile read latency.
+    pub p90: u64,
+    /// The 99th percentile read latency.
+    pub p99: u64,
+    /// The highest read latency.
+    pub max: u64,
 }
 
 /// Statistics about the Soup data-flow.
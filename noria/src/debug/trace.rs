@@ -0,0 +1,23 @@
+use crate::internal::*;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+/// A record of one traced write passing through one node.
+///
+/// Writes are traced when they are issued through a [`Table`](crate::Table) handle that has a
+/// trace id set with [`Table::trace_writes`](crate::Table::trace_writes).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceEvent {
+    /// The node the write passed through.
+    pub node: NodeIndex,
+    /// The domain (and shard) that processed the write at this node.
+    pub domain: (DomainIndex, usize),
+    /// When the node finished processing the write, in microseconds since the UNIX epoch.
+    pub at: u64,
+    /// The number of records the node received.
+    pub records_in: usize,
+    /// The number of records the node emitted to its children in the same domain.
+    ///
+    /// Egress nodes hand their output to other domains, and so always report zero here.
+    pub records_out: usize,
+}
@@ -245,7 +245,7 @@ pub mod results {
 
 /// Noria errors.
 pub mod error {
-    pub use crate::table::TableError;
+    pub use crate::table::{LoadError, TableError};
     pub use crate::view::ViewError;
 }
 
@@ -297,11 +297,11 @@ impl<T> From<T> for Tagged<T> {
 
 pub use crate::controller::{ControllerDescriptor, ControllerHandle};
 pub use crate::data::{DataType, Modification, Operation, TableOperation};
-pub use crate::table::Table;
-pub use crate::view::View;
+pub use crate::table::{LoadSummary, Table};
+pub use crate::view::{ReadLimit, View};
 
 #[doc(hidden)]
-pub use crate::table::Input;
+pub use crate::table::{Input, WriteReply};
 
 #[doc(hidden)]
 pub use crate::view::{ReadQuery, ReadReply, ReadReplyBatch};
@@ -336,6 +336,19 @@ pub fn shard_by(dt: &DataType, shards: usize) -> usize {
         DataType::UnsignedInt(n) => n as usize % shards,
         DataType::BigInt(n) => n as usize % shards,
         DataType::UnsignedBigInt(n) => n as usize % shards,
+        DataType::Decimal(mut m, mut scale) => {
+            // equal decimals must end up on the same shard, as must integral decimals and the
+            // integers they are equal to
+            while scale > 0 && m % 10 == 0 {
+                m /= 10;
+                scale -= 1;
+            }
+            if scale == 0 {
+                m as usize % shards
+            } else {
+                (m as usize ^ usize::from(scale)) % shards
+            }
+        }
         DataType::Text(..) | DataType::TinyText(..) => {
             use std::hash::Hasher;
             let mut hasher = ahash::AHasher::new_with_keys(0x3306, 0x6033);
@@ -343,6 +356,15 @@ pub fn shard_by(dt: &DataType, shards: usize) -> usize {
             hasher.write(s.as_bytes());
             hasher.finish() as usize % shards
         }
+        DataType::Bytes(ref b) => {
+            use std::hash::Hasher;
+            let mut hasher = ahash::AHasher::new_with_keys(0x3306, 0x6033);
+            hasher.write(&b[..]);
+            hasher.finish() as usize % shards
+        }
+        DataType::Uuid(ref u) => u128::from_be_bytes(**u) as usize % shards,
+        DataType::Bool(b) => b as usize % shards,
+        DataType::Float(f) => crate::data::float_key(f) as usize % shards,
         // a bit hacky: send all NULL values to the first shard
         DataType::None => 0,
         ref x => {
@@ -8,14 +8,14 @@ use futures_util::{
     future, future::TryFutureExt, ready, stream::futures_unordered::FuturesUnordered,
     stream::TryStreamExt,
 };
-use nom_sql::CreateTableStatement;
+use nom_sql::{ColumnConstraint, CreateTableStatement, SqlType};
 use petgraph::graph::NodeIndex;
 use std::collections::HashMap;
 use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
-use std::{fmt, io};
+use std::{fmt, io, mem};
 use tokio::io::AsyncWriteExt;
 use tokio_tower::multiplex;
 use tower_balance::p2c::Balance;
@@ -27,7 +27,7 @@ use vec_map::VecMap;
 
 type Transport = AsyncBincodeStream<
     tokio::net::TcpStream,
-    Tagged<()>,
+    Tagged<WriteReply>,
     Tagged<LocalOrNot<Input>>,
     AsyncDestination,
 >;
@@ -115,7 +115,7 @@ macro_rules! row {
                                 row[coli] = Into::<$crate::DataType>::into(literal);
                             }
                             ColumnConstraint::AutoIncrement => {
-                                // TODO
+                                // the base table assigns an id to rows that leave this as None
                             }
                             _ => {}
                         }
@@ -272,6 +272,33 @@ pub(crate) type TableRpc = Buffer<
     Tagged<LocalOrNot<Input>>,
 >;
 
+/// The outcome of a bulk load through [`Table::load_delimited`].
+#[derive(Debug, Default)]
+pub struct LoadSummary {
+    /// The number of rows that were written to the table.
+    pub rows: usize,
+    /// Rows that were skipped because they could not be parsed or do not fit the table's schema,
+    /// as `(line, reason)` pairs.
+    ///
+    /// Line numbers start at 1.
+    pub errors: Vec<(usize, String)>,
+}
+
+/// A bulk load through [`Table::load_delimited`] that stopped before reaching the end of its
+/// input.
+#[derive(Debug, Fail)]
+#[fail(display = "bulk load stopped early: {}", error)]
+pub struct LoadError {
+    /// What had been loaded when the load stopped.
+    ///
+    /// Rows of the batch that failed to write are not counted, even though some of them may
+    /// have been applied.
+    pub summary: LoadSummary,
+    /// Why the load stopped.
+    #[cause]
+    pub error: TableError,
+}
+
 /// A failed [`Table`] operation.
 #[derive(Debug, Fail)]
 pub enum TableError {
@@ -292,6 +319,28 @@ pub enum TableError {
     /// The underlying connection to Noria produced an error.
     #[fail(display = "{}", _0)]
     TransportError(#[cause] failure::Error),
+
+    /// Reading the input of a bulk load failed.
+    #[fail(display = "failed to read input: {}", _0)]
+    InputError(#[cause] io::Error),
+
+    /// A `NULL` was given for a column declared `NOT NULL`.
+    #[fail(display = "column {} may not be NULL", _0)]
+    NullValue(String),
+
+    /// A value was given that does not fit the declared type of its column.
+    #[fail(display = "value {} does not fit column {} of type {:?}", _1, _0, _2)]
+    WrongType(String, DataType, SqlType),
+
+    /// A bulk load was asked to write batches of zero rows.
+    #[fail(display = "bulk loads need a non-zero batch size")]
+    ZeroBatchSize,
+
+    /// Inserts were rejected because the table already holds rows with the same keys.
+    ///
+    /// All other operations in the same batch were still applied.
+    #[fail(display = "duplicate keys: {:?}", _0)]
+    DuplicateKey(Vec<Vec<DataType>>),
 }
 
 impl From<Box<dyn std::error::Error + Send + Sync>> for TableError {
@@ -300,11 +349,34 @@ impl From<Box<dyn std::error::Error + Send + Sync>> for TableError {
     }
 }
 
+/// A base table's acknowledgement of a batch of writes.
+#[doc(hidden)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WriteReply {
+    /// The keys of any inserts that the base rejected because it already held rows with those
+    /// keys.
+    pub rejected: Vec<Vec<DataType>>,
+    /// The auto-increment ids the base assigned to inserted rows, in the order of those inserts.
+    pub ids: Vec<DataType>,
+}
+
+fn check_reply(reply: Tagged<WriteReply>) -> Result<Tagged<Vec<DataType>>, TableError> {
+    if reply.v.rejected.is_empty() {
+        Ok(Tagged {
+            tag: reply.tag,
+            v: reply.v.ids,
+        })
+    } else {
+        Err(TableError::DuplicateKey(reply.v.rejected))
+    }
+}
+
 #[doc(hidden)]
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Input {
     pub dst: LocalNodeIndex,
     pub data: Vec<TableOperation>,
+    pub trace: Option<u64>,
 }
 
 impl fmt::Debug for Input {
@@ -312,6 +384,7 @@ impl fmt::Debug for Input {
         fmt.debug_struct("Input")
             .field("dst", &self.dst)
             .field("data", &self.data)
+            .field("trace", &self.trace)
             .finish()
     }
 }
@@ -381,6 +454,7 @@ impl TableBuilder {
             table_name: self.table_name,
             schema: self.schema,
             dst_is_local: false,
+            trace: None,
 
             shard_addrs: addrs,
             shards: conns,
@@ -392,6 +466,16 @@ impl TableBuilder {
 
 /// A `Table` is used to perform writes, deletes, and other operations to data in base tables.
 ///
+/// Writes issued one after the other through a `Table`, where each write is awaited before the
+/// next is issued, are applied in that order: a write is only acknowledged once its base table
+/// has processed it, every write to a given key goes to the same shard of the base, and updates
+/// travel between domains over ordered channels. Views therefore never observe the effects of
+/// two such writes to the same key out of order, even when they are computed in other domains
+/// or shards. Note that acknowledgement only means that the base has applied the write; views
+/// reflect it shortly after. Writes issued concurrently (e.g., by calling `Service::call` on a
+/// `Table` several times without waiting in between) may be sent over different connections,
+/// and carry no ordering guarantee relative to one another.
+///
 /// If you create multiple `Table` handles from a single `ControllerHandle`, they may share
 /// connections to the Soup workers. For this reason, `Table` is *not* `Send` or `Sync`. To get a
 /// handle that can be sent to a different thread (i.e., one with its own dedicated connections),
@@ -407,6 +491,7 @@ pub struct Table {
     table_name: String,
     schema: Option<CreateTableStatement>,
     dst_is_local: bool,
+    trace: Option<u64>,
 
     shards: Vec<TableRpc>,
     shard_addrs: Vec<SocketAddr>,
@@ -426,6 +511,7 @@ impl fmt::Debug for Table {
             .field("table_name", &self.table_name)
             .field("schema", &self.schema)
             .field("dst_is_local", &self.dst_is_local)
+            .field("trace", &self.trace)
             .field("shard_addrs", &self.shard_addrs)
             .finish()
     }
@@ -436,7 +522,7 @@ impl Table {
     fn input(
         &mut self,
         mut i: Input,
-    ) -> impl Future<Output = Result<Tagged<()>, TableError>> + Send {
+    ) -> impl Future<Output = Result<Tagged<Vec<DataType>>, TableError>> + Send {
         let span = if crate::trace_next_op() {
             Some(tracing::trace_span!(
                 "table-request",
@@ -507,7 +593,10 @@ impl Table {
             let _guard = span.as_ref().map(tracing::Span::enter);
             tracing::trace!("submit request");
             future::Either::Right(future::Either::Left(
-                self.shards[0].call(request).map_err(TableError::from),
+                self.shards[0]
+                    .call(request)
+                    .map_err(TableError::from)
+                    .and_then(|reply| future::ready(check_reply(reply))),
             ))
         } else {
             if self.key.is_empty() {
@@ -543,12 +632,14 @@ impl Table {
                             LocalOrNot::for_local_transfer(Input {
                                 dst: i.dst,
                                 data: rs,
+                                trace: i.trace,
                             })
                         }
                     } else {
                         LocalOrNot::new(Input {
                             dst: i.dst,
                             data: rs,
+                            trace: i.trace,
                         })
                     };
                     let request = Tagged::from(p);
@@ -573,9 +664,16 @@ impl Table {
 
             future::Either::Right(future::Either::Right(
                 wait_for
-                    .try_for_each(|_| async { Ok(()) })
                     .map_err(TableError::from)
-                    .map_ok(Tagged::from),
+                    .try_fold(
+                        WriteReply::default(),
+                        |mut acc, reply: Tagged<WriteReply>| {
+                            acc.rejected.extend(reply.v.rejected);
+                            acc.ids.extend(reply.v.ids);
+                            future::ready(Ok(acc))
+                        },
+                    )
+                    .and_then(|reply| future::ready(check_reply(Tagged::from(reply)))),
             ))
         }
     }
@@ -583,7 +681,7 @@ impl Table {
 
 impl Service<Vec<TableOperation>> for Table {
     type Error = TableError;
-    type Response = <TableRpc as Service<Tagged<LocalOrNot<Input>>>>::Response;
+    type Response = Tagged<()>;
 
     #[cfg(not(doc))]
     type Future = impl Future<Output = Result<Tagged<()>, TableError>> + Send;
@@ -598,8 +696,13 @@ impl Service<Vec<TableOperation>> for Table {
     }
 
     fn call(&mut self, ops: Vec<TableOperation>) -> Self::Future {
-        let i = self.prep_records(ops);
-        self.input(i)
+        match self.prep_records(ops) {
+            Ok(i) => future::Either::Left(self.input(i).map_ok(|reply| Tagged {
+                tag: reply.tag,
+                v: (),
+            })),
+            Err(e) => future::Either::Right(async move { Err(e) }),
+        }
     }
 }
 
@@ -614,6 +717,16 @@ impl Table {
         self.dst_is_local = true;
     }
 
+    /// Tag all subsequent writes through this handle with the given trace id, or stop tagging
+    /// them if `trace` is `None`.
+    ///
+    /// Every node that processes a tagged write records an event, and the events for a trace id
+    /// can later be retrieved with `ControllerHandle::trace`. Writes that are batched together
+    /// with a tagged write at the base are traced along with it.
+    pub fn trace_writes(&mut self, trace: Option<u64>) {
+        self.trace = trace;
+    }
+
     /// Get the list of columns in this base table.
     ///
     /// Note that this will *not* be updated if the underlying recipe changes and adds or removes
@@ -631,7 +744,6 @@ impl Table {
     }
 
     fn inject_dropped_cols(&self, r: &mut TableOperation) {
-        use std::mem;
         let ndropped = self.dropped.len();
         if ndropped != 0 {
             // inject defaults for dropped columns
@@ -716,15 +828,159 @@ impl Table {
         }
     }
 
-    fn prep_records(&self, mut ops: Vec<TableOperation>) -> Input {
+    fn coerce_columns(&self, r: &mut TableOperation) {
+        let schema = match self.schema {
+            Some(ref schema) => schema,
+            None => return,
+        };
+
+        // UUID columns are declared as BINARY(16), but applications usually write UUIDs in their
+        // textual form, so we parse those here before they reach the base table. Similarly, MySQL
+        // clients write booleans as 0 and 1.
+        let parse = |coli: usize, v: &mut DataType| match schema.fields.get(coli) {
+            Some(f) if f.sql_type == SqlType::Binary(16) && v.is_string() => {
+                let parsed = DataType::parse_uuid((&*v).into());
+                if let Ok(u) = parsed {
+                    *v = u;
+                }
+            }
+            Some(f) if f.sql_type == SqlType::Bool && v.is_integer() => {
+                *v = DataType::Bool(i64::from(&*v) != 0);
+            }
+            _ => {}
+        };
+        let parse_modification = |coli: usize, m: &mut Modification| match *m {
+            Modification::Set(ref mut v) | Modification::Apply(_, ref mut v) => parse(coli, v),
+            Modification::None => {}
+        };
+
+        match *r {
+            TableOperation::Insert(ref mut row) => {
+                for (coli, v) in row.iter_mut().enumerate() {
+                    parse(coli, v);
+                }
+            }
+            TableOperation::InsertOrUpdate {
+                ref mut row,
+                ref mut update,
+            } => {
+                for (coli, v) in row.iter_mut().enumerate() {
+                    parse(coli, v);
+                }
+                for (coli, m) in update.iter_mut().enumerate() {
+                    parse_modification(coli, m);
+                }
+            }
+            TableOperation::Delete { ref mut key } => {
+                for (v, &coli) in key.iter_mut().zip(&self.key) {
+                    parse(coli, v);
+                }
+            }
+            TableOperation::Update {
+                ref mut key,
+                ref mut set,
+            } => {
+                for (v, &coli) in key.iter_mut().zip(&self.key) {
+                    parse(coli, v);
+                }
+                for (coli, m) in set.iter_mut().enumerate() {
+                    parse_modification(coli, m);
+                }
+            }
+        }
+    }
+
+    /// Checks the given values against the `NOT NULL` constraints and types of their columns.
+    ///
+    /// Only numeric columns are type checked, and only for whether the value is a number at all.
+    /// Auto-increment columns and columns with a default may be left `NULL`, since the base table
+    /// fills those in.
+    fn check_columns<'a, I>(&self, values: I) -> Result<(), TableError>
+    where
+        I: IntoIterator<Item = (usize, &'a DataType)>,
+    {
+        let schema = match self.schema {
+            Some(ref schema) => schema,
+            None => return Ok(()),
+        };
+
+        for (coli, v) in values {
+            let spec = match schema.fields.get(coli) {
+                Some(spec) => spec,
+                None => continue,
+            };
+
+            if v.is_none() {
+                let not_null = spec
+                    .constraints
+                    .iter()
+                    .any(|c| *c == ColumnConstraint::NotNull);
+                let generated = spec.constraints.iter().any(|c| match *c {
+                    ColumnConstraint::AutoIncrement | ColumnConstraint::DefaultValue(_) => true,
+                    _ => false,
+                });
+                if not_null && !generated {
+                    return Err(TableError::NullValue(spec.column.name.clone()));
+                }
+                continue;
+            }
+
+            let integral = match *v {
+                DataType::UnsignedInt(_) | DataType::UnsignedBigInt(_) => true,
+                _ => v.is_integer(),
+            };
+            let fits = match spec.sql_type {
+                SqlType::Tinyint(_) | SqlType::Int(_) | SqlType::Bigint(_) => integral,
+                SqlType::Double | SqlType::Float | SqlType::Real | SqlType::Decimal(..) => {
+                    integral || v.is_real() || v.is_decimal() || v.is_float()
+                }
+                _ => true,
+            };
+            if !fits {
+                return Err(TableError::WrongType(
+                    spec.column.name.clone(),
+                    v.clone(),
+                    spec.sql_type.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_operation(&self, r: &TableOperation) -> Result<(), TableError> {
+        let set = |ms: &'_ [Modification]| {
+            ms.iter().enumerate().filter_map(|(coli, m)| match *m {
+                Modification::Set(ref v) => Some((coli, v)),
+                Modification::Apply(..) | Modification::None => None,
+            })
+        };
+
+        match *r {
+            TableOperation::Insert(ref row) => self.check_columns(row.iter().enumerate()),
+            TableOperation::InsertOrUpdate {
+                ref row,
+                ref update,
+            } => {
+                self.check_columns(row.iter().enumerate())?;
+                self.check_columns(set(update))
+            }
+            TableOperation::Update { set: ref ms, .. } => self.check_columns(set(ms)),
+            TableOperation::Delete { .. } => Ok(()),
+        }
+    }
+
+    fn prep_records(&self, mut ops: Vec<TableOperation>) -> Result<Input, TableError> {
         for r in &mut ops {
+            self.coerce_columns(r);
+            self.check_operation(r)?;
             self.inject_dropped_cols(r);
         }
 
-        Input {
+        Ok(Input {
             dst: self.node,
             data: ops,
-        }
+            trace: self.trace,
+        })
     }
 
     async fn quick_n_dirty<Request, R>(
@@ -748,6 +1004,34 @@ impl Table {
             .await
     }
 
+    /// Insert a single row of data into this base table, and return the id that the table
+    /// assigned to its auto-increment column.
+    ///
+    /// Returns `None` if the table has no auto-increment column, or if `u` already provided a
+    /// value for it.
+    pub async fn insert_returning_id<V>(&mut self, u: V) -> Result<Option<DataType>, TableError>
+    where
+        V: Into<Vec<DataType>>,
+    {
+        let i = self.prep_records(vec![TableOperation::Insert(u.into())])?;
+        future::poll_fn(|cx| self.poll_ready(cx)).await?;
+        Ok(self.input(i).await?.v.pop())
+    }
+
+    /// Insert many rows of data into this base table in a single batch.
+    pub async fn insert_many<I, V>(&mut self, rows: I) -> Result<(), TableError>
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Vec<DataType>>,
+    {
+        self.quick_n_dirty(
+            rows.into_iter()
+                .map(|row| TableOperation::Insert(row.into()))
+                .collect::<Vec<_>>(),
+        )
+        .await
+    }
+
     /// Perform multiple operation on this base table.
     pub async fn perform_all<I, V>(&mut self, i: I) -> Result<(), TableError>
     where
@@ -767,6 +1051,20 @@ impl Table {
             .await
     }
 
+    /// Delete the rows with each of the given keys from this base table in a single batch.
+    pub async fn delete_many<I, K>(&mut self, keys: I) -> Result<(), TableError>
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<Vec<DataType>>,
+    {
+        self.quick_n_dirty(
+            keys.into_iter()
+                .map(|key| TableOperation::Delete { key: key.into() })
+                .collect::<Vec<_>>(),
+        )
+        .await
+    }
+
     /// Update the row with the given key in this base table.
     ///
     /// `u` is a set of column-modification pairs, where for each pair `(i, m)`, the modification
@@ -823,4 +1121,154 @@ impl Table {
         }])
         .await
     }
+
+    /// Bulk-load rows of delimited text (e.g., CSV or TSV) from `input` into this base table.
+    ///
+    /// Every line of `input` holds one row, with fields separated by `delimiter`. Fields may be
+    /// enclosed in double quotes (with `""` for a literal quote), but may not span lines; empty
+    /// fields become `NULL`. If the table has a SQL schema, each field is coerced to its column's
+    /// type, and otherwise it is taken to be an integer, a real or text, whichever parses first.
+    /// Callers must skip any header line themselves.
+    ///
+    /// Rows are written `batch_size` at a time, and `progress` is called with the total number of
+    /// rows written after each batch. Rows that fail to parse or to fit the table's schema do not
+    /// abort the load, but are reported in the returned [`LoadSummary`]. If reading the input or
+    /// writing a batch fails, the load stops, and the returned [`LoadError`] says how far it got.
+    pub async fn load_delimited<R, F>(
+        &mut self,
+        input: R,
+        delimiter: char,
+        batch_size: usize,
+        mut progress: F,
+    ) -> Result<LoadSummary, LoadError>
+    where
+        R: io::BufRead,
+        F: FnMut(usize),
+    {
+        let mut summary = LoadSummary::default();
+        if batch_size == 0 {
+            return Err(LoadError {
+                summary,
+                error: TableError::ZeroBatchSize,
+            });
+        }
+
+        let mut batch = Vec::with_capacity(batch_size);
+        for (i, line) in input.lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    return Err(LoadError {
+                        summary,
+                        error: TableError::InputError(e),
+                    })
+                }
+            };
+            if line.is_empty() {
+                continue;
+            }
+
+            match self.parse_delimited_row(&line, delimiter) {
+                Ok(op) => batch.push(op),
+                Err(e) => summary.errors.push((i + 1, e)),
+            }
+
+            if batch.len() == batch_size {
+                let ops = mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                let n = ops.len();
+                if let Err(error) = self.quick_n_dirty(ops).await {
+                    return Err(LoadError { summary, error });
+                }
+                summary.rows += n;
+                progress(summary.rows);
+            }
+        }
+
+        if !batch.is_empty() {
+            let n = batch.len();
+            if let Err(error) = self.quick_n_dirty(batch).await {
+                return Err(LoadError { summary, error });
+            }
+            summary.rows += n;
+            progress(summary.rows);
+        }
+        Ok(summary)
+    }
+
+    /// Parses a line of delimited text into an insert, checking it like `insert` would.
+    fn parse_delimited_row(&self, line: &str, delimiter: char) -> Result<TableOperation, String> {
+        let fields = split_delimited(line, delimiter)?;
+        if fields.len() != self.columns.len() {
+            return Err(format!(
+                "expected {} fields, got {}",
+                self.columns.len(),
+                fields.len()
+            ));
+        }
+
+        let row = fields
+            .into_iter()
+            .enumerate()
+            .map(|(coli, field)| {
+                let sql_type = self
+                    .schema
+                    .as_ref()
+                    .and_then(|s| s.fields.get(coli))
+                    .map(|f| &f.sql_type);
+                parse_field(field, sql_type)
+                    .map_err(|e| format!("column {}: {}", self.columns[coli], e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut op = TableOperation::Insert(row);
+        self.coerce_columns(&mut op);
+        self.check_operation(&op).map_err(|e| e.to_string())?;
+        Ok(op)
+    }
+}
+
+/// Splits a line of delimited text into its fields, removing quotes around quoted fields.
+fn split_delimited(line: &str, delimiter: char) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            c if c == delimiter && !quoted => fields.push(mem::replace(&mut field, String::new())),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("unterminated quoted field"));
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn parse_field(field: String, sql_type: Option<&SqlType>) -> Result<DataType, String> {
+    if field.is_empty() {
+        return Ok(DataType::None);
+    }
+
+    match sql_type {
+        Some(sql_type) => DataType::parse_as(&field, sql_type)
+            .map_err(|e| format!("could not parse \"{}\": {}", field, e)),
+        None => Ok(if let Ok(i) = field.parse::<i64>() {
+            i.into()
+        } else if let Ok(f) = field.parse::<f64>() {
+            f.into()
+        } else {
+            field.into()
+        }),
+    }
 }
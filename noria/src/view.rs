@@ -123,6 +123,10 @@ pub enum ReadQuery {
         keys: Vec<Vec<DataType>>,
         /// Whether to block if a partial replay is triggered
         block: bool,
+        /// Only return a prefix or suffix of the rows for each key
+        limit: Option<ReadLimit>,
+        /// Arguments for the conditions the reader filters its rows by
+        args: Vec<DataType>,
     },
     /// Read the size of a leaf view
     Size {
@@ -131,6 +135,29 @@ pub enum ReadQuery {
     },
 }
 
+/// Restricts a lookup to the first or last rows, or to a page of rows, for each key of a view that
+/// keeps its rows ordered (see `Migration::order_reader_by`).
+///
+/// For views that do not keep their rows ordered, an arbitrary subset of the rows is returned. In
+/// particular, `Last(n)` then returns the same rows as `First(n)`, not the last rows of any order.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ReadLimit {
+    /// Return at most this many rows from the start of the order.
+    First(usize),
+    /// Return at most this many rows from the end of the order, starting with the last row.
+    Last(usize),
+    /// Skip `offset` rows from the start of the order, and return at most `count` of the rows
+    /// that follow.
+    Page {
+        /// Number of rows to skip.
+        offset: usize,
+        /// Maximum number of rows to return.
+        count: usize,
+    },
+    /// Return only the number of matching rows, as a single row with a single column.
+    Count,
+}
+
 #[doc(hidden)]
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ReadReply<D = ReadReplyBatch> {
@@ -146,6 +173,7 @@ pub struct ViewBuilder {
     pub node: NodeIndex,
     pub columns: Vec<String>,
     pub schema: Option<Vec<ColumnSpecification>>,
+    pub key: Vec<usize>,
     pub shards: Vec<SocketAddr>,
 }
 
@@ -160,6 +188,7 @@ impl ViewBuilder {
         let columns = self.columns.clone();
         let shards = self.shards.clone();
         let schema = self.schema.clone();
+        let key = self.key.clone();
 
         let mut addrs = Vec::with_capacity(shards.len());
         let mut conns = Vec::with_capacity(shards.len());
@@ -201,6 +230,7 @@ impl ViewBuilder {
             node,
             schema,
             columns,
+            key,
             shard_addrs: addrs,
             shards: conns,
             tracer,
@@ -217,6 +247,7 @@ pub struct View {
     node: NodeIndex,
     columns: Vec<String>,
     schema: Option<Vec<ColumnSpecification>>,
+    key: Vec<usize>,
 
     shards: Vec<ViewRpc>,
     shard_addrs: Vec<SocketAddr>,
@@ -254,6 +285,18 @@ impl Service<(Vec<Vec<DataType>>, bool)> for View {
     }
 
     fn call(&mut self, (keys, block): (Vec<Vec<DataType>>, bool)) -> Self::Future {
+        self.request(keys, block, None, Vec::new())
+    }
+}
+
+impl View {
+    fn request(
+        &mut self,
+        keys: Vec<Vec<DataType>>,
+        block: bool,
+        limit: Option<ReadLimit>,
+        args: Vec<DataType>,
+    ) -> impl Future<Output = Result<Vec<Results>, ViewError>> + Send {
         let span = if crate::trace_next_op() {
             Some(tracing::trace_span!(
                 "view-request",
@@ -270,6 +313,8 @@ impl Service<(Vec<Vec<DataType>>, bool)> for View {
                 target: (self.node, 0),
                 keys,
                 block,
+                limit,
+                args,
             });
 
             let _guard = span.as_ref().map(tracing::Span::enter);
@@ -324,6 +369,8 @@ impl Service<(Vec<Vec<DataType>>, bool)> for View {
                         target: (node, shardi),
                         keys: shard_queries,
                         block,
+                        limit,
+                        args: args.clone(),
                     });
 
                     let _guard = span.as_ref().map(tracing::Span::enter);
@@ -370,6 +417,11 @@ impl View {
         self.schema.as_deref()
     }
 
+    /// Get the indices of the columns that lookups into this view are keyed by.
+    pub fn key(&self) -> &[usize] {
+        &self.key
+    }
+
     /// Get the current size of this view.
     ///
     /// Note that you must also continue to poll this `View` for the returned future to resolve.
@@ -435,6 +487,58 @@ impl View {
         let rs = self.multi_lookup(vec![Vec::from(key)], block).await?;
         Ok(rs.into_iter().next().unwrap().into_iter().next())
     }
+
+    /// Retrieve only the first or last query results, or a page of them, for the given parameter
+    /// value.
+    ///
+    /// If the view keeps its rows ordered, the rows are returned without sorting them at read
+    /// time. The method will block if the results are not yet available only when `block` is
+    /// `true`.
+    pub async fn lookup_limited(
+        &mut self,
+        key: &[DataType],
+        limit: ReadLimit,
+        block: bool,
+    ) -> Result<Results, ViewError> {
+        future::poll_fn(|cx| self.poll_ready(cx)).await?;
+        let rs = self
+            .request(vec![Vec::from(key)], block, Some(limit), Vec::new())
+            .await?;
+        Ok(rs.into_iter().next().unwrap())
+    }
+
+    /// Retrieve the query results for the given parameter value that match the conditions the
+    /// view's reader filters its rows by, given the arguments `args` for those conditions.
+    ///
+    /// For example, a view filtered by distance (see `Migration::filter_reader_by`) takes the
+    /// point to measure the distance from as arguments. The method will block if the results are
+    /// not yet available only when `block` is `true`.
+    pub async fn lookup_with_args(
+        &mut self,
+        key: &[DataType],
+        args: &[DataType],
+        block: bool,
+    ) -> Result<Results, ViewError> {
+        future::poll_fn(|cx| self.poll_ready(cx)).await?;
+        let rs = self
+            .request(vec![Vec::from(key)], block, None, Vec::from(args))
+            .await?;
+        Ok(rs.into_iter().next().unwrap())
+    }
+
+    /// Retrieve the number of query results for the given parameter value.
+    ///
+    /// Only the count is sent back by the reader, not the rows themselves.
+    pub async fn count(&mut self, key: &[DataType]) -> Result<usize, ViewError> {
+        let rs = self.lookup_limited(key, ReadLimit::Count, true).await?;
+        let count = rs.into_iter().next().expect("count reply has one row");
+        Ok(i128::from(&count[0]) as usize)
+    }
+
+    /// Check whether there are any query results for the given parameter value.
+    pub async fn contains(&mut self, key: &[DataType]) -> Result<bool, ViewError> {
+        Ok(self.count(key).await? != 0)
+    }
 }
 
 #[derive(Debug, Default)]
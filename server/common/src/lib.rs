@@ -22,6 +22,10 @@ impl SizeOf for DataType {
 
         let inner = match *self {
             DataType::Text(ref t) => size_of_val(t) as u64 + t.to_bytes().len() as u64,
+            DataType::Bytes(ref b) => {
+                size_of_val(b) as u64 + size_of_val(&**b) as u64 + b.len() as u64
+            }
+            DataType::Uuid(ref u) => size_of_val(u) as u64 + size_of_val(&**u) as u64,
             _ => 0u64,
         };
 
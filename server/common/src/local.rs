@@ -144,6 +144,8 @@ pub enum KeyType<'a> {
     Quad((DataType, DataType, DataType, DataType)),
     Quin((DataType, DataType, DataType, DataType, DataType)),
     Sex((DataType, DataType, DataType, DataType, DataType, DataType)),
+    /// A compound key with more than six columns.
+    Multi(Vec<DataType>),
 }
 
 impl<'a> KeyType<'a> {
@@ -181,7 +183,7 @@ impl<'a> KeyType<'a> {
                 more().clone(),
                 more().clone(),
             )),
-            _ => unimplemented!(),
+            n => KeyType::Multi((0..n).map(|_| more().clone()).collect()),
         }
     }
 }
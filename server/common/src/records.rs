@@ -136,6 +136,22 @@ impl Records {
     {
         self.has(q, false)
     }
+
+    /// Cancel out any positive that is immediately followed by a negative for the same row (or
+    /// vice versa), since forwarding such pairs only makes downstream operators do useless work.
+    pub fn coalesce(&mut self) {
+        let mut kept: Vec<Record> = Vec::with_capacity(self.0.len());
+        for r in self.0.drain(..) {
+            if let Some(last) = kept.last() {
+                if last.is_positive() != r.is_positive() && last.rec() == r.rec() {
+                    kept.pop();
+                    continue;
+                }
+            }
+            kept.push(r);
+        }
+        self.0 = kept;
+    }
 }
 
 impl Deref for Records {
@@ -174,3 +190,30 @@ impl Into<Records> for Vec<(Vec<DataType>, bool)> {
         Records(self.into_iter().map(Into::into).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesce_cancels_adjacent_pairs() {
+        let a = vec![DataType::from(1), DataType::from("a")];
+        let b = vec![DataType::from(2), DataType::from("b")];
+
+        let mut rs: Records = vec![
+            (a.clone(), true),
+            (b.clone(), true),
+            (b.clone(), false),
+            (a.clone(), false),
+            (b.clone(), false),
+        ]
+        .into();
+        rs.coalesce();
+        assert_eq!(rs, vec![(b.clone(), false)].into());
+
+        // only adjacent pairs are cancelled
+        let mut rs: Records = vec![(a.clone(), true), (b.clone(), true), (a.clone(), false)].into();
+        rs.coalesce();
+        assert_eq!(rs.len(), 3);
+    }
+}
@@ -1,13 +1,35 @@
+use crate::ops::filter::FilterCondition;
 use crate::prelude::*;
 use ahash::RandomState;
 use common::SizeOf;
+use noria::ReadLimit;
 use rand::prelude::*;
-use std::borrow::Cow;
-use std::sync::Arc;
+use std::borrow::{Borrow, Cow};
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time;
+
+/// Number of keys sampled for every key evicted by `WriteHandle::evict_least_recent_keys`.
+const EVICTION_SAMPLES: usize = 4;
+
+/// Number of slots `ReadTimes` hashes the keys of a reader onto.
+const READ_TIME_SLOTS: usize = 1 << 16;
 
 /// Allocate a new end-user facing result table.
 pub(crate) fn new(cols: usize, key: &[usize]) -> (SingleReadHandle, WriteHandle) {
-    new_inner(cols, key, None)
+    new_inner(cols, key, None, None)
+}
+
+/// Allocate a new end-user facing result table that keeps the rows for each key ordered by the
+/// given column.
+pub(crate) fn new_ordered(
+    cols: usize,
+    key: &[usize],
+    order: usize,
+) -> (SingleReadHandle, WriteHandle) {
+    new_inner(cols, key, Some(order), None)
 }
 
 /// Allocate a new partially materialized end-user facing result table.
@@ -16,17 +38,19 @@ pub(crate) fn new(cols: usize, key: &[usize]) -> (SingleReadHandle, WriteHandle)
 pub(crate) fn new_partial<F>(
     cols: usize,
     key: &[usize],
+    order: Option<usize>,
     trigger: F,
 ) -> (SingleReadHandle, WriteHandle)
 where
     F: Fn(&mut dyn Iterator<Item = &[DataType]>) -> bool + 'static + Send + Sync,
 {
-    new_inner(cols, key, Some(Arc::new(trigger)))
+    new_inner(cols, key, order, Some(Arc::new(trigger)))
 }
 
 fn new_inner(
     cols: usize,
     key: &[usize],
+    order: Option<usize>,
     trigger: Option<Arc<dyn Fn(&mut dyn Iterator<Item = &[DataType]>) -> bool + Send + Sync>>,
 ) -> (SingleReadHandle, WriteHandle) {
     let contiguous = {
@@ -63,18 +87,48 @@ fn new_inner(
         _ => make!(Many),
     };
 
+    let (ordered, order) = match order {
+        Some(column) => {
+            let (r, w) = evmap::Options::default()
+                .with_hasher(RandomState::default())
+                .construct();
+            let w = OrderedWriter {
+                column,
+                rows: Default::default(),
+                handle: w,
+                pending: Vec::new(),
+            };
+            (Some((column, r)), Some(w))
+        }
+        None => (None, None),
+    };
+
+    let latencies = Arc::new(ReadLatencies::new());
+
+    // only partial readers evict, so only they need to know which keys were read recently
+    let read_times = trigger.as_ref().map(|_| Arc::new(ReadTimes::new()));
+
+    let r = SingleReadHandle {
+        handle: r,
+        trigger,
+        key: Vec::from(key),
+        ordered,
+        filter: None,
+        latency: latencies.register(),
+        latencies: Arc::clone(&latencies),
+        read_times: read_times.clone(),
+    };
     let w = WriteHandle {
-        partial: trigger.is_some(),
+        partial: r.trigger.is_some(),
         handle: w,
         key: Vec::from(key),
         cols,
         contiguous,
         mem_size: 0,
-    };
-    let r = SingleReadHandle {
-        handle: r,
-        trigger,
-        key: Vec::from(key),
+        order,
+        latencies,
+        filled: Default::default(),
+        read_times,
     };
 
     (r, w)
@@ -83,6 +137,222 @@ fn new_inner(
 mod multir;
 mod multiw;
 
+/// Read latencies recorded through a single `SingleReadHandle`, in microseconds.
+type Latencies = Arc<Mutex<hdrhistogram::Histogram<u64>>>;
+
+fn new_latencies() -> hdrhistogram::Histogram<u64> {
+    // significant figures used for read latencies; the histogram grows as needed
+    hdrhistogram::Histogram::new(3).expect("3 significant figures is valid")
+}
+
+/// Read latencies observed by the readers of a single reader shard.
+///
+/// Every handle to the reader records into a histogram of its own, so that concurrent reads never
+/// contend on a lock. The histograms are only merged when statistics are collected.
+struct ReadLatencies {
+    handles: Mutex<HandleLatencies>,
+}
+
+struct HandleLatencies {
+    /// The latencies recorded by handles that have since been dropped.
+    retired: hdrhistogram::Histogram<u64>,
+    live: Vec<Latencies>,
+}
+
+impl ReadLatencies {
+    fn new() -> Self {
+        ReadLatencies {
+            handles: Mutex::new(HandleLatencies {
+                retired: new_latencies(),
+                live: Vec::new(),
+            }),
+        }
+    }
+
+    /// Make a histogram for a new handle to record into.
+    fn register(&self) -> Latencies {
+        let h = Arc::new(Mutex::new(new_latencies()));
+        self.handles.lock().unwrap().live.push(Arc::clone(&h));
+        h
+    }
+
+    /// Merge the latencies recorded by all handles so far.
+    fn merged(&self) -> hdrhistogram::Histogram<u64> {
+        let mut handles = self.handles.lock().unwrap();
+        let HandleLatencies {
+            ref mut retired,
+            ref mut live,
+        } = *handles;
+
+        // adding can only fail if a value overflows the histogram, which it can't, as the
+        // histogram auto-resizes
+        live.retain(|h| {
+            if Arc::strong_count(h) > 1 {
+                return true;
+            }
+            // the handle is gone, so nothing records into this anymore
+            let _ = retired.add(&*h.lock().unwrap());
+            false
+        });
+
+        let mut merged = retired.clone();
+        for h in live.iter() {
+            let _ = merged.add(&*h.lock().unwrap());
+        }
+        merged
+    }
+}
+
+/// When the keys of a partial reader were last read, in milliseconds since the reader was created.
+///
+/// Reads happen concurrently on many threads, so instead of keeping an exact time for every key,
+/// which would take a lock, keys are hashed onto a fixed number of atomic slots. Keys that share a
+/// slot look as recently read as the most recently read among them, which at worst makes eviction
+/// keep such a key around for longer than it should.
+struct ReadTimes {
+    start: time::Instant,
+    hasher: RandomState,
+    slots: Box<[AtomicU64]>,
+}
+
+impl ReadTimes {
+    fn new() -> Self {
+        ReadTimes {
+            start: time::Instant::now(),
+            hasher: RandomState::new(),
+            slots: (0..READ_TIME_SLOTS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    fn slot(&self, key: &[DataType]) -> usize {
+        let mut h = self.hasher.build_hasher();
+        key.iter().for_each(|k| k.hash(&mut h));
+        h.finish() as usize % self.slots.len()
+    }
+
+    /// Record that `key` was just read.
+    fn touch(&self, key: &[DataType]) {
+        // never-read keys are at 0, so count from 1
+        let now = self.start.elapsed().as_millis() as u64 + 1;
+        self.slots[self.slot(key)].store(now, Ordering::Relaxed);
+    }
+
+    /// When `key` was last read, or 0 if it has never been read.
+    fn get(&self, key: &[DataType]) -> u64 {
+        self.slots[self.slot(key)].load(Ordering::Relaxed)
+    }
+}
+
+/// The rows for a single key of a reader, sorted by a single column.
+type SortedRows = Arc<Vec<Vec<DataType>>>;
+
+/// The read side of the sorted rows of a reader.
+type OrderedRows = evmap::ReadHandle<Vec<DataType>, SortedRows, (), RandomState>;
+
+enum OrderedOp {
+    Add(Record),
+    Empty(Vec<DataType>),
+}
+
+/// Keeps a sorted copy of the rows of a reader so that the first or last rows for a key can be
+/// read without sorting them at read time.
+///
+/// The sorted rows for each key are published through a second `evmap`, so reads never take a
+/// lock. Changes are buffered and only published after the owning `WriteHandle` has refreshed
+/// the underlying `evmap`, so that readers never observe writes here that are not also visible
+/// there.
+struct OrderedWriter {
+    column: usize,
+    /// The sorted rows for each key. The rows are shared with the published map, and are only
+    /// copied when a key changes.
+    rows: HashMap<Vec<DataType>, SortedRows, RandomState>,
+    handle: evmap::WriteHandle<Vec<DataType>, SortedRows, (), RandomState>,
+    pending: Vec<OrderedOp>,
+}
+
+impl OrderedWriter {
+    fn publish(&mut self, key: &[usize]) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let column = self.column;
+        let cmp = |a: &Vec<DataType>, b: &[DataType]| cmp_ordered(column, a, b);
+
+        let mut changed = HashSet::new();
+        for op in self.pending.drain(..) {
+            match op {
+                OrderedOp::Add(Record::Positive(r)) => {
+                    let k: Vec<_> = key.iter().map(|&c| r[c].clone()).collect();
+                    let rs = Arc::make_mut(self.rows.entry(k.clone()).or_default());
+                    let i = match rs.binary_search_by(|x| cmp(x, &r)) {
+                        Ok(i) | Err(i) => i,
+                    };
+                    rs.insert(i, r);
+                    changed.insert(k);
+                }
+                OrderedOp::Add(Record::Negative(r)) => {
+                    let k: Vec<_> = key.iter().map(|&c| r[c].clone()).collect();
+                    if let Some(rs) = self.rows.get_mut(&k) {
+                        let rs = Arc::make_mut(rs);
+                        if let Ok(i) = rs.binary_search_by(|x| cmp(x, &r)) {
+                            rs.remove(i);
+                        }
+                        changed.insert(k);
+                    }
+                }
+                OrderedOp::Empty(k) => {
+                    if self.rows.remove(&k).is_some() {
+                        changed.insert(k);
+                    }
+                }
+            }
+        }
+
+        for k in changed {
+            match self.rows.get(&k) {
+                Some(rs) if !rs.is_empty() => {
+                    let rs = Arc::clone(rs);
+                    self.handle.update(k, rs);
+                }
+                _ => {
+                    self.rows.remove(&k);
+                    self.handle.empty(k);
+                }
+            }
+        }
+        self.handle.refresh();
+    }
+}
+
+/// Compares rows by the given column, and then by all their columns, which is the order in which
+/// ordered readers keep their rows.
+fn cmp_ordered(column: usize, a: &[DataType], b: &[DataType]) -> std::cmp::Ordering {
+    a[column].cmp(&b[column]).then_with(|| a.cmp(b))
+}
+
+/// Passes the rows of the sorted `rs` that `limit` selects to `then`.
+fn limit_sorted<R, F, T>(rs: &[R], limit: ReadLimit, then: &mut F) -> T
+where
+    R: Borrow<Vec<DataType>>,
+    F: FnMut(&mut dyn ExactSizeIterator<Item = &Vec<DataType>>) -> T,
+{
+    let (start, end) = match limit {
+        ReadLimit::First(n) => (0, n.min(rs.len())),
+        ReadLimit::Last(n) => (rs.len() - n.min(rs.len()), rs.len()),
+        ReadLimit::Page { offset, count } => {
+            let start = offset.min(rs.len());
+            (start, start + count.min(rs.len() - start))
+        }
+        ReadLimit::Count => unreachable!("counts don't need sorted rows"),
+    };
+    let mut rs = rs[start..end].iter().map(Borrow::<Vec<DataType>>::borrow);
+    match limit {
+        ReadLimit::Last(_) => then(&mut rs.rev()),
+        _ => then(&mut rs),
+    }
+}
+
 fn key_to_single(k: Key) -> Cow<DataType> {
     assert_eq!(k.len(), 1);
     match k {
@@ -111,6 +381,11 @@ pub(crate) struct WriteHandle {
     key: Vec<usize>,
     contiguous: bool,
     mem_size: usize,
+    order: Option<OrderedWriter>,
+    latencies: Arc<ReadLatencies>,
+    /// The keys of a partial reader that are currently filled, so that eviction can sample them.
+    filled: indexmap::IndexSet<Vec<DataType>, RandomState>,
+    read_times: Option<Arc<ReadTimes>>,
 }
 
 type Key<'a> = Cow<'a, [DataType]>;
@@ -130,6 +405,9 @@ impl<'a> MutWriteHandleEntry<'a> {
             .handle
             .meta_get_and(Cow::Borrowed(&*self.key), |rs| rs.is_empty())
         {
+            if self.handle.read_times.is_some() {
+                self.handle.filled.insert(self.key.to_vec());
+            }
             self.handle.handle.clear(self.key)
         } else {
             unreachable!("attempted to fill already-filled key");
@@ -145,7 +423,12 @@ impl<'a> MutWriteHandleEntry<'a> {
             })
             .map(|r| r.0.unwrap_or(0))
             .unwrap_or(0);
-        self.handle.mem_size = self.handle.mem_size.checked_sub(size as usize).unwrap();
+        let size = size as usize * self.handle.copies();
+        self.handle.mem_size = self.handle.mem_size.checked_sub(size).unwrap();
+        if let Some(ref mut order) = self.handle.order {
+            order.pending.push(OrderedOp::Empty(self.key.to_vec()));
+        }
+        self.handle.filled.swap_remove(&self.key[..]);
         self.handle.handle.empty(self.key)
     }
 }
@@ -230,6 +513,20 @@ impl WriteHandle {
 
     pub(crate) fn swap(&mut self) {
         self.handle.refresh();
+        if let Some(ref mut order) = self.order {
+            order.publish(&self.key[..]);
+        }
+    }
+
+    /// How many copies of each row this handle keeps.
+    ///
+    /// Readers that keep their rows ordered hold a sorted copy of every row next to the `evmap`.
+    fn copies(&self) -> usize {
+        if self.order.is_some() {
+            2
+        } else {
+            1
+        }
     }
 
     /// Add a new set of records to the backlog.
@@ -239,7 +536,14 @@ impl WriteHandle {
     where
         I: IntoIterator<Item = Record>,
     {
-        let mem_delta = self.handle.add(&self.key[..], self.cols, rs);
+        let mem_delta = if let Some(ref mut order) = self.order {
+            let rs: Vec<_> = rs.into_iter().collect();
+            order.pending.extend(rs.iter().cloned().map(OrderedOp::Add));
+            self.handle.add(&self.key[..], self.cols, rs)
+        } else {
+            self.handle.add(&self.key[..], self.cols, rs)
+        };
+        let mem_delta = mem_delta * self.copies() as isize;
         if mem_delta > 0 {
             self.mem_size += mem_delta as usize;
         } else if mem_delta < 0 {
@@ -250,24 +554,78 @@ impl WriteHandle {
         }
     }
 
+    /// Summarize the latencies of the reads served from this reader so far.
+    pub(crate) fn read_latency(&self) -> Option<noria::debug::stats::ReadLatency> {
+        let h = self.latencies.merged();
+        if h.is_empty() {
+            return None;
+        }
+        Some(noria::debug::stats::ReadLatency {
+            count: h.len(),
+            p50: h.value_at_quantile(0.5),
+            p90: h.value_at_quantile(0.9),
+            p99: h.value_at_quantile(0.99),
+            max: h.max(),
+        })
+    }
+
     pub(crate) fn is_partial(&self) -> bool {
         self.partial
     }
 
-    /// Evict `count` randomly selected keys from state and return them along with the number of
+    /// Evict up to `n` keys that have not been read recently from state and return the number of
     /// bytes that will be freed once the underlying `evmap` applies the operation.
-    pub(crate) fn evict_random_keys(&mut self, rng: &mut ThreadRng, mut n: usize) -> u64 {
+    ///
+    /// Like `SingleState::evict_least_recent_keys`, this samples a few keys for every key to
+    /// evict, and evicts the least recently read ones. Only partial readers can evict keys, since
+    /// full readers have no way to fill them again.
+    pub(crate) fn evict_least_recent_keys(&mut self, rng: &mut ThreadRng, n: usize) -> u64 {
+        let read_times = match self.read_times {
+            Some(ref read_times) => read_times,
+            None => return 0,
+        };
+
+        let len = self.filled.len();
+        let mut indices: Vec<usize> = if len <= n * EVICTION_SAMPLES {
+            (0..len).collect()
+        } else {
+            (0..n * EVICTION_SAMPLES)
+                .map(|_| rng.gen_range(0, len))
+                .collect()
+        };
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut candidates: Vec<_> = indices
+            .into_iter()
+            .map(|i| (read_times.get(&self.filled[i]), i))
+            .collect();
+        candidates.sort_unstable_by_key(|&(read, _)| read);
+        candidates.truncate(n);
+
+        // remove from the back, so that swap_remove_index leaves the remaining indices in place
+        let mut evict: Vec<_> = candidates.into_iter().map(|(_, i)| i).collect();
+        evict.sort_unstable_by(|a, b| b.cmp(a));
+
+        let copies = self.copies() as u64;
         let mut bytes_to_be_freed = 0;
-        if self.mem_size > 0 {
-            if self.handle.is_empty() {
-                unreachable!("mem size is {}, but map is empty", self.mem_size);
+        for i in evict {
+            let key = self
+                .filled
+                .swap_remove_index(i)
+                .expect("sampled index is in range");
+            let size: u64 = self
+                .handle
+                .meta_get_and(Cow::Borrowed(&key[..]), |rs| {
+                    rs.iter().map(SizeOf::deep_size_of).sum()
+                })
+                .and_then(|r| r.0)
+                .unwrap_or(0);
+            bytes_to_be_freed += size * copies;
+            if let Some(ref mut order) = self.order {
+                order.pending.push(OrderedOp::Empty(key.clone()));
             }
-
-            self.handle.empty_random_for_each(rng, n, |vs| {
-                let size: u64 = vs.iter().map(|r| r.deep_size_of() as u64).sum();
-                bytes_to_be_freed += size;
-                n -= 1;
-            });
+            self.handle.empty(Cow::Owned(key));
         }
 
         self.mem_size = self
@@ -295,11 +653,32 @@ impl SizeOf for WriteHandle {
 }
 
 /// Handle to get the state of a single shard of a reader.
-#[derive(Clone)]
 pub struct SingleReadHandle {
     handle: multir::Handle,
     trigger: Option<Arc<dyn Fn(&mut dyn Iterator<Item = &[DataType]>) -> bool + Send + Sync>>,
     key: Vec<usize>,
+    /// The column this reader orders its rows by, and its sorted rows.
+    ordered: Option<(usize, OrderedRows)>,
+    filter: Option<Arc<Vec<(usize, FilterCondition)>>>,
+    /// The latencies of reads through this handle, which each clone keeps separately.
+    latency: Latencies,
+    latencies: Arc<ReadLatencies>,
+    read_times: Option<Arc<ReadTimes>>,
+}
+
+impl Clone for SingleReadHandle {
+    fn clone(&self) -> Self {
+        SingleReadHandle {
+            handle: self.handle.clone(),
+            trigger: self.trigger.clone(),
+            key: self.key.clone(),
+            ordered: self.ordered.clone(),
+            filter: self.filter.clone(),
+            latency: self.latencies.register(),
+            latencies: Arc::clone(&self.latencies),
+            read_times: self.read_times.clone(),
+        }
+    }
 }
 
 impl std::fmt::Debug for SingleReadHandle {
@@ -308,11 +687,29 @@ impl std::fmt::Debug for SingleReadHandle {
             .field("handle", &self.handle)
             .field("has_trigger", &self.trigger.is_some())
             .field("key", &self.key)
+            .field("ordered", &self.ordered.is_some())
+            .field("filtered", &self.filter.is_some())
             .finish()
     }
 }
 
 impl SingleReadHandle {
+    /// Only return the rows that match the given conditions from `try_find_filtered_and`.
+    pub(crate) fn set_filter(&mut self, filter: Arc<Vec<(usize, FilterCondition)>>) {
+        self.filter = Some(filter);
+    }
+
+    /// Record that a read from this reader took `took`, including any time spent waiting.
+    ///
+    /// Every clone of a handle records into a histogram of its own, so threads or connections that
+    /// read concurrently should each use their own clone.
+    pub fn record_latency(&self, took: time::Duration) {
+        let us = took.as_micros() as u64;
+        // recording can only fail if the value overflows the histogram, which it can't, as the
+        // histogram auto-resizes
+        let _ = self.latency.lock().unwrap().record(us);
+    }
+
     /// Trigger a replay of a missing key from a partially materialized view.
     pub fn trigger<'a, I>(&self, keys: I) -> bool
     where
@@ -345,13 +742,144 @@ impl SingleReadHandle {
             .meta_get_and(key, &mut then)
             .ok_or(())
             .map(|(mut records, meta)| {
-                if records.is_none() && self.trigger.is_none() {
+                if records.is_some() {
+                    if let Some(ref read_times) = self.read_times {
+                        read_times.touch(key);
+                    }
+                } else if self.trigger.is_none() {
                     records = Some(then(&evmap::Values::default()));
                 }
                 (records, meta)
             })
     }
 
+    /// Find the first or last entries, or a page of entries, that matched the given conditions.
+    ///
+    /// If this reader keeps its rows ordered, the rows are passed to `then` in that order (or in
+    /// reverse order for `ReadLimit::Last`). Otherwise, an arbitrary subset of the matching rows
+    /// is passed, in no particular order; in particular, `ReadLimit::Last(n)` then passes the
+    /// same rows `ReadLimit::First(n)` would, rather than the last rows of any order.
+    ///
+    /// Holes in partially materialized state are returned as `Ok((None, _))`.
+    pub fn try_find_limited_and<F, T>(
+        &self,
+        key: &[DataType],
+        limit: ReadLimit,
+        mut then: F,
+    ) -> Result<(Option<T>, i64), ()>
+    where
+        F: FnMut(&mut dyn ExactSizeIterator<Item = &Vec<DataType>>) -> T,
+    {
+        if let ReadLimit::Count = limit {
+            return self.try_find_and(key, |rs| {
+                then(&mut std::iter::once(&vec![DataType::from(rs.len())]))
+            });
+        }
+
+        let (column, ordered) = match self.ordered {
+            Some((column, ref ordered)) => (column, ordered),
+            None => {
+                return self.try_find_and(key, |rs| match limit {
+                    ReadLimit::First(n) | ReadLimit::Last(n) => {
+                        then(&mut rs.iter().take(n).collect::<Vec<_>>().into_iter())
+                    }
+                    ReadLimit::Page { offset, count } => then(
+                        &mut rs
+                            .iter()
+                            .skip(offset)
+                            .take(count)
+                            .collect::<Vec<_>>()
+                            .into_iter(),
+                    ),
+                    ReadLimit::Count => unreachable!("handled above"),
+                });
+            }
+        };
+
+        let rows = ordered.read();
+        self.try_find_and(key, |all| {
+            // the sorted rows are published right after the evmap, so they may briefly lag behind
+            // it. if they don't hold as many rows as the evmap does, sort the evmap's rows instead.
+            let sorted = rows
+                .as_ref()
+                .and_then(|rows| rows.get(key))
+                .and_then(|rs| rs.iter().next())
+                .filter(|rs| rs.len() == all.len());
+            match sorted {
+                Some(rs) => limit_sorted(&rs[..], limit, &mut then),
+                None => {
+                    let mut rs: Vec<_> = all.iter().collect();
+                    rs.sort_by(|a, b| cmp_ordered(column, a, b));
+                    limit_sorted(&rs[..], limit, &mut then)
+                }
+            }
+        })
+    }
+
+    /// Find the entries that matched the given conditions and this reader's filter (if any),
+    /// restricted by `limit` as in `try_find_limited_and`.
+    ///
+    /// `args` are the arguments of the current read, which the filter may refer to. The limit is
+    /// applied to the rows that pass the filter.
+    ///
+    /// Holes in partially materialized state are returned as `Ok((None, _))`.
+    pub fn try_find_filtered_and<F, T>(
+        &self,
+        key: &[DataType],
+        args: &[DataType],
+        limit: Option<ReadLimit>,
+        mut then: F,
+    ) -> Result<(Option<T>, i64), ()>
+    where
+        F: FnMut(&mut dyn ExactSizeIterator<Item = &Vec<DataType>>) -> T,
+    {
+        let filter = match (self.filter.as_ref(), limit) {
+            (Some(filter), _) => filter,
+            (None, None) => return self.try_find_and(key, |rs| then(&mut rs.into_iter())),
+            (None, Some(limit)) => return self.try_find_limited_and(key, limit, then),
+        };
+        let matches = |r: &Vec<DataType>| {
+            filter
+                .iter()
+                .all(|(i, cond)| cond.matches_with_args(&r[*i], r, args))
+        };
+
+        match limit {
+            None => self.try_find_and(key, |rs| {
+                then(
+                    &mut rs
+                        .into_iter()
+                        .filter(|r| matches(r))
+                        .collect::<Vec<_>>()
+                        .into_iter(),
+                )
+            }),
+            Some(ReadLimit::Count) => self.try_find_and(key, |rs| {
+                let n = rs.into_iter().filter(|r| matches(r)).count();
+                then(&mut std::iter::once(&vec![DataType::from(n)]))
+            }),
+            Some(limit) => {
+                // fetch all the rows in order, and then limit the ones that pass the filter
+                let all = match limit {
+                    ReadLimit::Last(_) => ReadLimit::Last(usize::max_value()),
+                    _ => ReadLimit::First(usize::max_value()),
+                };
+                self.try_find_limited_and(key, all, |rs| {
+                    let rs = rs.filter(|r| matches(r));
+                    match limit {
+                        ReadLimit::First(n) | ReadLimit::Last(n) => {
+                            then(&mut rs.take(n).collect::<Vec<_>>().into_iter())
+                        }
+                        ReadLimit::Page { offset, count } => {
+                            then(&mut rs.skip(offset).take(count).collect::<Vec<_>>().into_iter())
+                        }
+                        ReadLimit::Count => unreachable!("handled above"),
+                    }
+                })
+            }
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.handle.len()
     }
@@ -564,4 +1092,247 @@ mod tests {
             .0
             .unwrap());
     }
+
+    #[test]
+    fn filtered_reads() {
+        use crate::ops::filter::{FilterCondition, Value};
+        use nom_sql::Operator;
+
+        let (mut r, mut w) = new_ordered(3, &[0], 1);
+        r.set_filter(Arc::new(vec![(
+            1,
+            FilterCondition::Comparison(Operator::Greater, Value::Argument(0)),
+        )]));
+        w.add(vec![
+            Record::Positive(vec![1.into(), 3.into(), "c".into()]),
+            Record::Positive(vec![1.into(), 1.into(), "a".into()]),
+            Record::Positive(vec![1.into(), 2.into(), "b".into()]),
+        ]);
+        w.swap();
+
+        let rows = |args: &[DataType], limit: Option<ReadLimit>| {
+            r.try_find_filtered_and(&[1.into()], args, limit, |rs| {
+                rs.map(|r| r[2].clone()).collect::<Vec<_>>()
+            })
+            .unwrap()
+            .0
+            .unwrap()
+        };
+        assert_eq!(rows(&[1.into()], None).len(), 2);
+        assert_eq!(
+            rows(&[1.into()], Some(ReadLimit::First(1))),
+            vec!["b".into()]
+        );
+        assert_eq!(
+            rows(&[0.into()], Some(ReadLimit::Last(2))),
+            vec!["c".into(), "b".into()]
+        );
+        let count = r
+            .try_find_filtered_and(&[1.into()], &[2.into()], Some(ReadLimit::Count), |rs| {
+                rs.next().unwrap()[0].clone()
+            })
+            .unwrap()
+            .0
+            .unwrap();
+        assert_eq!(count, DataType::from(1usize));
+        // without its argument, the condition is unknown, and so never matches
+        assert!(rows(&[], None).is_empty());
+    }
+
+    #[test]
+    fn ordered_limits() {
+        let (r, mut w) = new_ordered(2, &[0], 1);
+        w.add(vec![
+            Record::Positive(vec![1.into(), 3.into()]),
+            Record::Positive(vec![1.into(), 1.into()]),
+            Record::Positive(vec![1.into(), 2.into()]),
+            Record::Positive(vec![2.into(), 9.into()]),
+        ]);
+
+        // not visible until after a swap
+        assert_eq!(
+            r.try_find_limited_and(&[1.into()], ReadLimit::First(2), |rs| rs.len()),
+            Err(())
+        );
+        w.swap();
+
+        let first = r
+            .try_find_limited_and(&[1.into()], ReadLimit::First(2), |rs| {
+                rs.map(|r| r[1].clone()).collect::<Vec<_>>()
+            })
+            .unwrap()
+            .0
+            .unwrap();
+        assert_eq!(first, vec![1.into(), 2.into()]);
+
+        w.add(vec![
+            Record::Negative(vec![1.into(), 3.into()]),
+            Record::Positive(vec![1.into(), 0.into()]),
+        ]);
+        w.swap();
+
+        let last = r
+            .try_find_limited_and(&[1.into()], ReadLimit::Last(5), |rs| {
+                rs.map(|r| r[1].clone()).collect::<Vec<_>>()
+            })
+            .unwrap()
+            .0
+            .unwrap();
+        assert_eq!(last, vec![2.into(), 1.into(), 0.into()]);
+
+        assert_eq!(
+            r.try_find_limited_and(&[3.into()], ReadLimit::First(1), |rs| rs.len())
+                .unwrap()
+                .0,
+            Some(0)
+        );
+
+        let page = |offset, count| {
+            r.try_find_limited_and(&[1.into()], ReadLimit::Page { offset, count }, |rs| {
+                rs.map(|r| r[1].clone()).collect::<Vec<_>>()
+            })
+            .unwrap()
+            .0
+            .unwrap()
+        };
+        assert_eq!(page(1, 1), vec![DataType::from(1)]);
+        assert_eq!(page(1, 5), vec![DataType::from(1), DataType::from(2)]);
+        assert!(page(3, 1).is_empty());
+    }
+
+    #[test]
+    fn ordered_reads_sort_rows_not_yet_published_in_order() {
+        let (r, mut w) = new_ordered(2, &[0], 1);
+        let read = |limit| {
+            r.try_find_limited_and(&[1.into()], limit, |rs| {
+                rs.map(|r| r[1].clone()).collect::<Vec<_>>()
+            })
+            .unwrap()
+            .0
+            .unwrap()
+        };
+
+        w.add(vec![
+            Record::Positive(vec![1.into(), 3.into()]),
+            Record::Positive(vec![1.into(), 1.into()]),
+            Record::Positive(vec![1.into(), 2.into()]),
+        ]);
+        // make the rows visible in the evmap, but not (yet) in the sorted rows
+        w.handle.refresh();
+        assert_eq!(read(ReadLimit::Last(2)), vec![3.into(), 2.into()]);
+
+        // likewise if the sorted rows are only missing some of the rows
+        w.swap();
+        w.add(vec![Record::Positive(vec![1.into(), 0.into()])]);
+        w.handle.refresh();
+        assert_eq!(read(ReadLimit::First(2)), vec![0.into(), 1.into()]);
+    }
+
+    #[test]
+    fn ordered_rows_are_counted() {
+        let rs = vec![
+            Record::Positive(vec![1.into(), 3.into()]),
+            Record::Positive(vec![1.into(), 1.into()]),
+            Record::Positive(vec![2.into(), 9.into()]),
+        ];
+        let (_, mut plain) = new(2, &[0]);
+        let (_, mut ordered) = new_ordered(2, &[0], 1);
+        plain.add(rs.clone());
+        ordered.add(rs);
+        plain.swap();
+        ordered.swap();
+        assert_eq!(ordered.deep_size_of(), 2 * plain.deep_size_of());
+
+        let key = [DataType::from(1)];
+        plain.mut_with_key(&key[..]).mark_hole();
+        ordered.mut_with_key(&key[..]).mark_hole();
+        assert_eq!(ordered.deep_size_of(), 2 * plain.deep_size_of());
+        ordered.swap();
+
+        let key = [DataType::from(2)];
+        ordered.mut_with_key(&key[..]).mark_hole();
+        assert_eq!(ordered.deep_size_of(), 0);
+    }
+
+    #[test]
+    fn counts() {
+        let (r, mut w) = new(2, &[0]);
+        w.add(vec![
+            Record::Positive(vec![1.into(), 1.into()]),
+            Record::Positive(vec![1.into(), 2.into()]),
+        ]);
+        w.swap();
+
+        let count = |key: DataType| {
+            r.try_find_limited_and(&[key], ReadLimit::Count, |rs| {
+                rs.map(|r| r[0].clone()).collect::<Vec<_>>()
+            })
+            .unwrap()
+            .0
+            .unwrap()
+        };
+        assert_eq!(count(1.into()), vec![DataType::from(2usize)]);
+        assert_eq!(count(2.into()), vec![DataType::from(0usize)]);
+    }
+
+    #[test]
+    fn merges_latencies_of_all_handles() {
+        let (r, w) = new(1, &[0]);
+        assert!(w.read_latency().is_none());
+
+        let a = r.clone();
+        let b = r.clone();
+        a.record_latency(time::Duration::from_micros(10));
+        b.record_latency(time::Duration::from_micros(20));
+        b.record_latency(time::Duration::from_micros(30));
+
+        // latencies survive the handle that recorded them
+        drop(b);
+        let l = w.read_latency().unwrap();
+        assert_eq!(l.count, 3);
+        assert_eq!(l.max, 30);
+
+        a.record_latency(time::Duration::from_micros(40));
+        let l = w.read_latency().unwrap();
+        assert_eq!(l.count, 4);
+        assert_eq!(l.max, 40);
+    }
+
+    #[test]
+    fn evicts_least_recently_read_keys() {
+        let (r, mut w) = new_partial(1, &[0], None, |_: &mut dyn Iterator<Item = &[DataType]>| {
+            true
+        });
+        w.swap();
+
+        // use keys that have read time slots of their own, so reading one key doesn't make
+        // another look recently read
+        let read_times = Arc::clone(r.read_times.as_ref().unwrap());
+        let mut slots = HashSet::new();
+        let keys: Vec<_> = (0i32..)
+            .map(|k| [DataType::from(k)])
+            .filter(|k| slots.insert(read_times.slot(&k[..])))
+            .take(8)
+            .collect();
+        for k in &keys {
+            w.mut_with_key(&k[..]).mark_filled();
+            w.add(vec![Record::Positive(k.to_vec())]);
+        }
+        w.swap();
+
+        let read = |k: &[DataType]| r.try_find_and(k, |rs| rs.len()).unwrap().0;
+        for k in &keys[4..] {
+            assert_eq!(read(&k[..]), Some(1));
+        }
+
+        assert!(w.evict_least_recent_keys(&mut rand::thread_rng(), 4) > 0);
+        w.swap();
+
+        for k in &keys[..4] {
+            assert_eq!(read(&k[..]), None);
+        }
+        for k in &keys[4..] {
+            assert_eq!(read(&k[..]), Some(1));
+        }
+    }
 }
@@ -23,6 +23,22 @@ use crate::Readers;
 use timekeeper::{RealTime, SimpleTracker, ThreadTime, Timer, TimerSet};
 use tokio;
 
+#[cfg(debug_assertions)]
+mod sanity;
+
+/// Release builds don't track the rows nodes emit, so evictions have nothing to forget.
+#[cfg(not(debug_assertions))]
+mod sanity {
+    use crate::prelude::*;
+
+    #[derive(Default)]
+    pub(super) struct EmittedRows;
+
+    impl EmittedRows {
+        pub(super) fn evicted(&mut self, _: LocalNodeIndex, _: &[usize], _: &[Vec<DataType>]) {}
+    }
+}
+
 #[derive(Debug)]
 pub enum PollEvent {
     ResumePolling,
@@ -41,10 +57,17 @@ pub enum ProcessResult {
 pub struct Config {
     pub concurrent_replays: usize,
     pub replay_batch_timeout: time::Duration,
+    /// Log a warning whenever a node takes longer than this to process a single update.
+    pub slow_update_threshold: Option<time::Duration>,
+    /// Log a warning whenever a node turns a single update into more than this many records.
+    pub large_fanout_threshold: Option<usize>,
 }
 
 const BATCH_SIZE: usize = 256;
 
+/// The number of traced-write events each domain keeps before it starts dropping the oldest ones.
+const MAX_TRACE_EVENTS: usize = 10_000;
+
 #[derive(Debug)]
 enum DomainMode {
     Forwarding,
@@ -184,6 +207,8 @@ impl DomainBuilder {
 
             buffered_replay_requests: Default::default(),
             replay_batch_timeout: self.config.replay_batch_timeout,
+            slow_update_threshold: self.config.slow_update_threshold,
+            large_fanout_threshold: self.config.large_fanout_threshold,
             timed_purges: Default::default(),
 
             concurrent_replays: 0,
@@ -193,12 +218,16 @@ impl DomainBuilder {
 
             group_commit_queues,
 
+            traces: Default::default(),
+            emitted: Default::default(),
+
             state_size,
             total_time: Timer::new(),
             total_ptime: Timer::new(),
             wait_time: Timer::new(),
             process_times: TimerSet::new(),
             process_ptimes: TimerSet::new(),
+            records_processed: Default::default(),
 
             total_replay_time: Timer::new(),
             total_forward_time: Timer::new(),
@@ -248,16 +277,27 @@ pub struct Domain {
 
     buffered_replay_requests: HashMap<(Tag, usize), (time::Instant, HashSet<Vec<DataType>>, bool)>,
     replay_batch_timeout: time::Duration,
+    slow_update_threshold: Option<time::Duration>,
+    large_fanout_threshold: Option<usize>,
     delayed_for_self: VecDeque<Box<Packet>>,
 
     group_commit_queues: GroupCommitQueueSet,
 
+    /// events recorded for traced writes, oldest first
+    traces: VecDeque<(u64, noria::debug::trace::TraceEvent)>,
+
+    /// rows emitted by each node, used to catch unmatched negatives in debug builds.
+    /// release builds use a stub that tracks nothing.
+    emitted: sanity::EmittedRows,
+
     state_size: Arc<AtomicUsize>,
     total_time: Timer<SimpleTracker, RealTime>,
     total_ptime: Timer<SimpleTracker, ThreadTime>,
     wait_time: Timer<SimpleTracker, RealTime>,
     process_times: TimerSet<LocalNodeIndex, SimpleTracker, RealTime>,
     process_ptimes: TimerSet<LocalNodeIndex, SimpleTracker, ThreadTime>,
+    /// number of records each node has received during forward processing
+    records_processed: Map<u64>,
 
     /// time spent processing replays
     total_replay_time: Timer<SimpleTracker, RealTime>,
@@ -579,6 +619,16 @@ impl Domain {
             return;
         }
 
+        let records_in = m.len();
+        *self.records_processed.entry(me).or_insert(0) += records_in as u64;
+        let traced = m.trace();
+        // only pay for sampling a record if someone might want to see it
+        let watched =
+            if self.slow_update_threshold.is_some() || self.large_fanout_threshold.is_some() {
+                Some((time::Instant::now(), m.sample()))
+            } else {
+                None
+            };
         let (mut m, evictions) = {
             let mut n = self.nodes[me].borrow_mut();
             self.process_times.start(me);
@@ -599,6 +649,71 @@ impl Domain {
             self.process_ptimes.stop();
             self.process_times.stop();
 
+            if let Some((start, sample)) = watched {
+                let took = start.elapsed();
+                let records_out = m.as_ref().map(|m| m.len()).unwrap_or(0);
+                if self.slow_update_threshold.map_or(false, |t| took > t) {
+                    warn!(self.log, "slow update";
+                          "node" => n.global_addr().index(),
+                          "desc" => format!("{:?}", *n),
+                          "took" => ?took,
+                          "records" => records_in,
+                          "sample" => ?sample);
+                }
+                if self
+                    .large_fanout_threshold
+                    .map_or(false, |t| records_out > t)
+                {
+                    warn!(self.log, "large update fanout";
+                          "node" => n.global_addr().index(),
+                          "desc" => format!("{:?}", *n),
+                          "records_in" => records_in,
+                          "records_out" => records_out,
+                          "sample" => ?sample);
+                }
+            }
+
+            if let Some(trace) = traced {
+                if self.traces.len() == MAX_TRACE_EVENTS {
+                    self.traces.pop_front();
+                }
+                let at = time::SystemTime::now()
+                    .duration_since(time::UNIX_EPOCH)
+                    .map(|d| d.as_micros() as u64)
+                    .unwrap_or(0);
+                self.traces.push_back((
+                    trace,
+                    noria::debug::trace::TraceEvent {
+                        node: n.global_addr(),
+                        domain: (self.index, self.shard.unwrap_or(0)),
+                        at,
+                        records_in,
+                        records_out: m.as_ref().map(|m| m.len()).unwrap_or(0),
+                    },
+                ));
+            }
+
+            #[cfg(debug_assertions)]
+            {
+                if let Some(ref m) = m {
+                    if !n.is_base() {
+                        if let Err(r) = self.emitted.record(me, m.data()) {
+                            crit!(self.log, "negative for row that was never emitted";
+                                  "node" => n.global_addr().index(),
+                                  "desc" => format!("{:?}", *n),
+                                  "from" => ?src,
+                                  "records_in" => records_in,
+                                  "row" => ?r);
+                            panic!(
+                                "node {} emitted a negative for unknown row {:?}",
+                                n.global_addr().index(),
+                                r
+                            );
+                        }
+                    }
+                }
+            }
+
             if m.is_none() {
                 // no need to deal with our children if we're not sending them anything
                 return;
@@ -888,6 +1003,17 @@ impl Domain {
                                     state.add_key(&idx[..], None);
                                 }
                             }
+                            InitialState::OrderedLocal(columns) => {
+                                let state = self
+                                    .state
+                                    .get_mut(node)
+                                    .expect("ordered index on node without state");
+                                for column in columns {
+                                    info!(self.log, "told to prepare ordered state";
+                                           "column" => column);
+                                    state.add_ordered_key(column);
+                                }
+                            }
                             InitialState::PartialGlobal {
                                 gid,
                                 cols,
@@ -932,9 +1058,14 @@ impl Domain {
                                         tx
                                     })
                                     .collect::<Vec<_>>();
-                                let (r_part, w_part) = backlog::new_partial(
+                                let (order, filter) = self.nodes[node]
+                                    .borrow()
+                                    .with_reader(|r| (r.order(), r.filter().cloned()))
+                                    .unwrap();
+                                let (mut r_part, w_part) = backlog::new_partial(
                                     cols,
                                     &k[..],
+                                    order,
                                     move |misses: &mut dyn Iterator<Item = &[DataType]>| {
                                         let n = txs.len();
                                         if n == 1 {
@@ -964,6 +1095,9 @@ impl Domain {
                                         }
                                     },
                                 );
+                                if let Some(filter) = filter {
+                                    r_part.set_filter(filter);
+                                }
 
                                 let mut n = self.nodes[node].borrow_mut();
                                 tokio::task::block_in_place(|| {
@@ -986,7 +1120,17 @@ impl Domain {
                             }
                             InitialState::Global { gid, cols, key } => {
                                 use crate::backlog;
-                                let (r_part, w_part) = backlog::new(cols, &key[..]);
+                                let (order, filter) = self.nodes[node]
+                                    .borrow()
+                                    .with_reader(|r| (r.order(), r.filter().cloned()))
+                                    .unwrap();
+                                let (mut r_part, w_part) = match order {
+                                    Some(order) => backlog::new_ordered(cols, &key[..], order),
+                                    None => backlog::new(cols, &key[..]),
+                                };
+                                if let Some(filter) = filter {
+                                    r_part.set_filter(filter);
+                                }
 
                                 let mut n = self.nodes[node].borrow_mut();
                                 tokio::task::block_in_place(|| {
@@ -1369,6 +1513,7 @@ impl Domain {
                             total_replay_time: self.total_replay_time.num_nanoseconds(),
                             total_forward_time: self.total_forward_time.num_nanoseconds(),
                             wait_time: self.wait_time.num_nanoseconds(),
+                            queue_depth: executor.queued() as u64,
                         };
 
                         let node_stats = self
@@ -1425,6 +1570,12 @@ impl Domain {
                                     Default::default()
                                 };
 
+                                let read_latency = if n.is_reader() {
+                                    n.with_reader(|r| r.read_latency()).unwrap()
+                                } else {
+                                    None
+                                };
+
                                 if time.is_some() && ptime.is_some() {
                                     Some((
                                         node_index,
@@ -1432,9 +1583,23 @@ impl Domain {
                                             desc: format!("{:?}", n),
                                             process_time: time.unwrap(),
                                             process_ptime: ptime.unwrap(),
+                                            records: self
+                                                .records_processed
+                                                .get(local_index)
+                                                .cloned()
+                                                .unwrap_or(0),
+                                            rows: if n.is_reader() {
+                                                0
+                                            } else {
+                                                self.state
+                                                    .get(local_index)
+                                                    .map(|s| s.rows())
+                                                    .unwrap_or(0)
+                                            },
                                             mem_size,
                                             materialized: mat_state,
                                             probe_result,
+                                            read_latency,
                                         },
                                     ))
                                 } else {
@@ -1447,6 +1612,17 @@ impl Domain {
                             .send(ControlReplyPacket::Statistics(domain_stats, node_stats))
                             .unwrap();
                     }
+                    Packet::GetTrace { trace } => {
+                        let events = self
+                            .traces
+                            .iter()
+                            .filter(|&&(t, _)| t == trace)
+                            .map(|(_, e)| e.clone())
+                            .collect();
+                        self.control_reply_tx
+                            .send(ControlReplyPacket::Trace(events))
+                            .unwrap();
+                    }
                     Packet::UpdateStateSize => {
                         self.update_state_sizes();
                     }
@@ -2000,6 +2176,13 @@ impl Domain {
                             &self.log,
                         );
 
+                        #[cfg(debug_assertions)]
+                        {
+                            if let Some(ref m) = m {
+                                self.emitted.replayed(segment.node, m.data());
+                            }
+                        }
+
                         // ignore duplicate misses
                         misses.sort_unstable_by(|a, b| {
                             a.on.cmp(&b.on)
@@ -2652,6 +2835,7 @@ impl Domain {
             shard: Option<usize>,
             state: &mut StateMap,
             nodes: &DomainNodes,
+            emitted: &mut sanity::EmittedRows,
         ) {
             // TODO: this is a linear walk of replay paths -- we should make that not linear
             for (tag, ref path) in replay_paths {
@@ -2668,7 +2852,7 @@ impl Domain {
                     };
 
                     let mut keys = Vec::from(keys);
-                    walk_path(&path.path[..], &mut keys, *tag, shard, nodes, ex);
+                    walk_path(&path.path[..], &mut keys, *tag, shard, nodes, emitted, ex);
 
                     if let TriggerEndpoint::Local(_) = path.trigger {
                         let target = replay_paths[&tag].path.last().unwrap();
@@ -2686,6 +2870,11 @@ impl Domain {
                         }
 
                         state[target.node].evict_keys(*tag, &keys[..]);
+                        emitted.evicted(
+                            target.node,
+                            &target.partial_key.as_ref().unwrap()[..],
+                            &keys[..],
+                        );
                         trigger_downstream_evictions(
                             log,
                             &target.partial_key.as_ref().unwrap()[..],
@@ -2697,6 +2886,7 @@ impl Domain {
                             shard,
                             state,
                             nodes,
+                            emitted,
                         );
                     }
                 }
@@ -2709,18 +2899,21 @@ impl Domain {
             tag: Tag,
             shard: Option<usize>,
             nodes: &DomainNodes,
+            emitted: &mut sanity::EmittedRows,
             executor: &mut dyn Executor,
         ) {
             let mut from = path[0].node;
             for segment in path {
+                let key_columns = &segment.partial_key.as_ref().unwrap()[..];
                 nodes[segment.node].borrow_mut().process_eviction(
                     from,
-                    &segment.partial_key.as_ref().unwrap()[..],
+                    key_columns,
                     keys,
                     tag,
                     shard,
                     executor,
                 );
+                emitted.evicted(segment.node, key_columns, &keys[..]);
                 from = segment.node;
             }
         }
@@ -2805,9 +2998,15 @@ impl Domain {
                         if n.is_dropped() {
                             break; // Node was dropped. Give up.
                         } else if n.is_reader() {
-                            let freed_now = n.with_reader_mut(|r| r.evict_random_keys(16)).unwrap();
+                            let freed_now = n
+                                .with_reader_mut(|r| r.evict_least_recent_keys(16))
+                                .unwrap();
 
                             freed += freed_now;
+                            if !n.with_reader(|r| r.is_partial()).unwrap() {
+                                // full readers never evict, so there's no point in trying again
+                                break;
+                            }
                             if n.with_reader(|r| r.is_empty()).unwrap() {
                                 trace!(
                                     self.log,
@@ -2818,12 +3017,13 @@ impl Domain {
                             }
                         } else {
                             let (key_columns, keys, bytes) = {
-                                let k = self.state[node].evict_random_keys(16);
+                                let k = self.state[node].evict_least_recent_keys(16);
                                 (k.0.to_vec(), k.1, k.2)
                             };
                             freed += bytes;
 
                             if !keys.is_empty() {
+                                self.emitted.evicted(node, &key_columns[..], &keys[..]);
                                 trigger_downstream_evictions(
                                     &self.log,
                                     &key_columns[..],
@@ -2835,6 +3035,7 @@ impl Domain {
                                     self.shard,
                                     &mut self.state,
                                     &self.nodes,
+                                    &mut self.emitted,
                                 );
                             }
                             if self.state[node].is_empty() {
@@ -2864,7 +3065,15 @@ impl Domain {
                     .iter()
                     .position(|ps| ps.node == dst)
                     .expect("got eviction for non-local node");
-                walk_path(&path[i..], &mut keys, tag, self.shard, &mut self.nodes, ex);
+                walk_path(
+                    &path[i..],
+                    &mut keys,
+                    tag,
+                    self.shard,
+                    &self.nodes,
+                    &mut self.emitted,
+                    ex,
+                );
 
                 match trigger {
                     TriggerEndpoint::End { .. } | TriggerEndpoint::Local(..) => {
@@ -2881,6 +3090,7 @@ impl Domain {
                         }
                         if let Some(evicted) = self.state[target].evict_keys(tag, &keys) {
                             let key_columns = evicted.0.to_vec();
+                            self.emitted.evicted(target, &key_columns[..], &keys[..]);
                             trigger_downstream_evictions(
                                 &self.log,
                                 &key_columns[..],
@@ -2892,6 +3102,7 @@ impl Domain {
                                 self.shard,
                                 &mut self.state,
                                 &mut self.nodes,
+                                &mut self.emitted,
                             );
                         }
                     }
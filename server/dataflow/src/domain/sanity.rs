@@ -0,0 +1,211 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// The most rows and evicted keys we are willing to track for any one node.
+///
+/// Past this, the node is no longer checked, so that long-running debug builds don't end up
+/// holding a second copy of every materialization.
+const MAX_TRACKED_ROWS: usize = 1 << 20;
+
+/// Tracks, for every node in a domain, how many copies of each row it has emitted so far.
+///
+/// This lets debug builds catch operators that emit a negative for a row they never produced,
+/// which would otherwise silently corrupt any downstream aggregation. The counts are an
+/// over-approximation: replays and re-processed updates only ever add to them, so a negative
+/// that *is* flagged was genuinely never emitted.
+///
+/// When a partial key is evicted, the rows a node emitted under it are forgotten. Negatives for
+/// such rows may still flow (for example through stateless nodes on the replay path, or through
+/// a node whose other indices still hold the row), and the rows may be emitted again by a later
+/// replay, so negatives under an evicted key are never flagged from then on.
+pub(super) struct EmittedRows {
+    nodes: Map<Tracked>,
+    limit: usize,
+}
+
+#[derive(Default)]
+struct Tracked {
+    counts: HashMap<Vec<DataType>, usize>,
+    /// keys that have been evicted from this node, by the columns they were evicted on
+    evicted: HashMap<Vec<usize>, HashSet<Vec<DataType>>>,
+    evicted_keys: usize,
+    /// set once this node has outgrown the tracking limit
+    overflowed: bool,
+}
+
+impl Tracked {
+    fn was_evicted(&self, r: &[DataType]) -> bool {
+        self.evicted.iter().any(|(cols, keys)| {
+            let key: Vec<_> = cols.iter().map(|&c| r[c].clone()).collect();
+            keys.contains(&key)
+        })
+    }
+
+    fn enforce(&mut self, limit: usize) {
+        if self.counts.len() + self.evicted_keys > limit {
+            self.counts = HashMap::new();
+            self.evicted = HashMap::new();
+            self.evicted_keys = 0;
+            self.overflowed = true;
+        }
+    }
+}
+
+impl Default for EmittedRows {
+    fn default() -> Self {
+        EmittedRows {
+            nodes: Map::default(),
+            limit: MAX_TRACKED_ROWS,
+        }
+    }
+}
+
+impl EmittedRows {
+    /// Account for the records `node` just emitted.
+    ///
+    /// Positives in a batch are counted before its negatives, since operators are free to order
+    /// records within a batch as they like. Returns the first negative that does not correspond
+    /// to a previously emitted row.
+    pub(super) fn record(
+        &mut self,
+        node: LocalNodeIndex,
+        rs: &Records,
+    ) -> Result<(), Vec<DataType>> {
+        let t = self.nodes.entry(node).or_default();
+        if t.overflowed {
+            return Ok(());
+        }
+        for r in rs.iter().filter(|r| r.is_positive()) {
+            *t.counts.entry(r.rec().to_vec()).or_insert(0) += 1;
+        }
+        for r in rs.iter().filter(|r| !r.is_positive()) {
+            match t.counts.get_mut(r.rec()) {
+                Some(n) if *n > 1 => *n -= 1,
+                Some(_) => {
+                    t.counts.remove(r.rec());
+                }
+                None if t.was_evicted(r.rec()) => {}
+                None => return Err(r.rec().to_vec()),
+            }
+        }
+        t.enforce(self.limit);
+        Ok(())
+    }
+
+    /// Account for the records `node` produced while processing a replay.
+    ///
+    /// Replays carry existing state rather than changes to it, so only their positives are
+    /// counted, and nothing is checked.
+    pub(super) fn replayed(&mut self, node: LocalNodeIndex, rs: &Records) {
+        let t = self.nodes.entry(node).or_default();
+        if t.overflowed {
+            return;
+        }
+        for r in rs.iter().filter(|r| r.is_positive()) {
+            *t.counts.entry(r.rec().to_vec()).or_insert(0) += 1;
+        }
+        t.enforce(self.limit);
+    }
+
+    /// Forget the rows `node` emitted under the given keys, which have just been evicted.
+    pub(super) fn evicted(
+        &mut self,
+        node: LocalNodeIndex,
+        key_columns: &[usize],
+        keys: &[Vec<DataType>],
+    ) {
+        let t = match self.nodes.get_mut(node) {
+            Some(t) if !t.overflowed => t,
+            _ => return,
+        };
+        let evicted = t.evicted.entry(key_columns.to_vec()).or_default();
+        for key in keys {
+            if evicted.insert(key.clone()) {
+                t.evicted_keys += 1;
+            }
+        }
+        let evicted = &t.evicted[key_columns];
+        t.counts.retain(|r, _| {
+            let key: Vec<_> = key_columns.iter().map(|&c| r[c].clone()).collect();
+            !evicted.contains(&key)
+        });
+        t.enforce(self.limit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_catches_unmatched_negatives() {
+        let n = unsafe { LocalNodeIndex::make(0) };
+        let mut e = EmittedRows::default();
+
+        let a: Vec<DataType> = vec![1.into(), "a".into()];
+        let b: Vec<DataType> = vec![2.into(), "b".into()];
+
+        assert!(e.record(n, &vec![a.clone(), a.clone()].into()).is_ok());
+        assert!(e.record(n, &vec![(a.clone(), false)].into()).is_ok());
+        // retractions and their replacements may come in either order within a batch
+        assert!(e
+            .record(n, &vec![(a.clone(), false), (b.clone(), true)].into())
+            .is_ok());
+        assert_eq!(e.record(n, &vec![(a.clone(), false)].into()), Err(a));
+        assert!(e.record(n, &vec![(b, false)].into()).is_ok());
+    }
+
+    #[test]
+    fn it_forgets_evicted_rows() {
+        let n = unsafe { LocalNodeIndex::make(0) };
+        let mut e = EmittedRows::default();
+
+        let a: Vec<DataType> = vec![1.into(), "a".into()];
+        let b: Vec<DataType> = vec![2.into(), "b".into()];
+        let c: Vec<DataType> = vec![2.into(), "c".into()];
+
+        assert!(e.record(n, &vec![a.clone(), b.clone()].into()).is_ok());
+        e.evicted(n, &[0], &[vec![2.into()]]);
+        assert_eq!(e.nodes[n].counts.len(), 1);
+
+        // updates under the evicted key may still flow, and can't be checked
+        assert!(e.record(n, &vec![(c.clone(), false)].into()).is_ok());
+        // nor can those that follow the key being replayed again
+        e.replayed(n, &vec![b.clone()].into());
+        assert!(e.record(n, &vec![(b.clone(), false)].into()).is_ok());
+        assert!(e.record(n, &vec![(b, false)].into()).is_ok());
+        // but other keys still are
+        assert!(e.record(n, &vec![(a.clone(), false)].into()).is_ok());
+        assert_eq!(e.record(n, &vec![(a.clone(), false)].into()), Err(a));
+    }
+
+    #[test]
+    fn it_stops_tracking_past_the_limit() {
+        let n = unsafe { LocalNodeIndex::make(0) };
+        let m = unsafe { LocalNodeIndex::make(1) };
+        let mut e = EmittedRows {
+            limit: 2,
+            ..Default::default()
+        };
+
+        let a: Vec<DataType> = vec![1.into()];
+        let b: Vec<DataType> = vec![2.into()];
+        let c: Vec<DataType> = vec![3.into()];
+
+        assert!(e.record(n, &vec![a.clone(), b.clone()].into()).is_ok());
+        assert_eq!(
+            e.record(n, &vec![(c.clone(), false)].into()),
+            Err(c.clone())
+        );
+        e.replayed(n, &vec![c.clone()].into());
+        assert!(e.nodes[n].overflowed);
+        assert!(e.nodes[n].counts.is_empty());
+        assert!(e.record(n, &vec![(c.clone(), false)].into()).is_ok());
+
+        // evicted keys count towards the limit too
+        assert!(e.record(m, &vec![a.clone()].into()).is_ok());
+        e.evicted(m, &[0], &[b, c]);
+        assert!(e.nodes[m].overflowed);
+        assert!(e.record(m, &vec![(a, false)].into()).is_ok());
+    }
+}
@@ -3,9 +3,9 @@ use noria::internal::LocalOrNot;
 use std::time;
 
 pub struct GroupCommitQueueSet {
-    /// Packets that are queued to be persisted.
+    /// Packets that are queued to be persisted, along with the number of rows they contain.
     #[allow(clippy::vec_box)]
-    pending_packets: Map<(time::Instant, Vec<Box<Packet>>)>,
+    pending_packets: Map<(time::Instant, Vec<Box<Packet>>, usize)>,
     params: PersistenceParameters,
 }
 
@@ -35,7 +35,7 @@ impl GroupCommitQueueSet {
         let node = self
             .pending_packets
             .iter()
-            .find(|(_, &(first, ref ps))| now.duration_since(first) >= to && !ps.is_empty())
+            .find(|(_, &(first, ref ps, _))| now.duration_since(first) >= to && !ps.is_empty())
             .map(|(n, _)| n);
 
         if let Some(node) = node {
@@ -47,7 +47,9 @@ impl GroupCommitQueueSet {
 
     /// Merge any pending packets.
     fn flush_internal(&mut self, node: LocalNodeIndex) -> Option<Box<Packet>> {
-        Self::merge_packets(&mut self.pending_packets[node].1)
+        let pp = &mut self.pending_packets[node];
+        pp.2 = 0;
+        Self::merge_packets(&mut pp.1)
     }
 
     /// Add a new packet to be persisted, and if this triggered a flush return an iterator over the
@@ -57,14 +59,18 @@ impl GroupCommitQueueSet {
         let pp = self
             .pending_packets
             .entry(node)
-            .or_insert_with(|| (time::Instant::now(), Vec::new()));
+            .or_insert_with(|| (time::Instant::now(), Vec::new(), 0));
 
         if pp.1.is_empty() {
             pp.0 = time::Instant::now();
         }
 
+        if let Packet::Input { ref inner, .. } = *p {
+            pp.2 += unsafe { inner.deref() }.data.len();
+        }
+
         pp.1.push(p);
-        if pp.0.elapsed() >= self.params.flush_timeout {
+        if pp.2 >= self.params.queue_capacity || pp.0.elapsed() >= self.params.flush_timeout {
             self.flush_internal(node)
         } else {
             None
@@ -75,7 +81,7 @@ impl GroupCommitQueueSet {
     pub fn duration_until_flush(&self) -> Option<time::Duration> {
         self.pending_packets
             .values()
-            .filter(|(_, ps)| !ps.is_empty())
+            .filter(|(_, ps, _)| !ps.is_empty())
             .map(|p| {
                 self.params
                     .flush_timeout
@@ -93,6 +99,7 @@ impl GroupCommitQueueSet {
         let merged_dst = packets.peek().as_mut().unwrap().dst();
 
         let mut all_senders = vec![];
+        let mut merged_trace = None;
         let merged_data = packets.fold(Vec::new(), |mut acc, p| {
             match *p {
                 Packet::Input {
@@ -100,15 +107,16 @@ impl GroupCommitQueueSet {
                     src,
                     senders,
                 } => {
-                    let Input { dst, data } = unsafe { inner.take() };
+                    let Input { dst, data, trace } = unsafe { inner.take() };
 
                     assert_eq!(senders.len(), 0);
                     assert_eq!(merged_dst, dst);
-                    acc.extend(data);
+                    // every packet's operations go into the batch, so every packet needs an entry
+                    // to keep the operation indices of later senders lined up
+                    all_senders.push((src, data.len()));
 
-                    if let Some(src) = src {
-                        all_senders.push(src);
-                    }
+                    acc.extend(data);
+                    merged_trace = merged_trace.or(trace);
                 }
                 _ => unreachable!(),
             }
@@ -119,6 +127,7 @@ impl GroupCommitQueueSet {
             inner: LocalOrNot::new(Input {
                 dst: merged_dst,
                 data: merged_data,
+                trace: merged_trace,
             }),
             src: None,
             senders: all_senders,
@@ -135,3 +144,120 @@ impl GroupCommitQueueSet {
         Self::merge_committed_packets(packets.drain(..))
     }
 }
+
+/// Hand out what a base reports about a merged batch to the clients that sent it.
+///
+/// `senders` lists the packets that were merged into the batch, in order, each with its number of
+/// operations. `rejected` holds the index and key of every rejected insert, and `ids` the index
+/// and assigned id of every insert that was given an auto-increment id. Returns every sender that
+/// is waiting for an acknowledgement along with its share of both.
+pub(crate) fn split_replies(
+    senders: Vec<(Option<SourceChannelIdentifier>, usize)>,
+    rejected: Vec<(usize, Vec<DataType>)>,
+    ids: Vec<(usize, DataType)>,
+) -> Vec<(SourceChannelIdentifier, WriteReply)> {
+    let mut rejected = by_sender(&senders, rejected).into_iter();
+    let mut ids = by_sender(&senders, ids).into_iter();
+    senders
+        .into_iter()
+        .filter_map(|(src, _)| {
+            let reply = WriteReply {
+                rejected: rejected.next().unwrap(),
+                ids: ids.next().unwrap(),
+            };
+            src.map(|src| (src, reply))
+        })
+        .collect()
+}
+
+/// Groups values that are indexed by their operation's position in a merged batch by the sender
+/// of that operation.
+fn by_sender<T>(
+    senders: &[(Option<SourceChannelIdentifier>, usize)],
+    mut values: Vec<(usize, T)>,
+) -> Vec<Vec<T>> {
+    values.sort_by_key(|&(opi, _)| opi);
+    let mut values = values.into_iter().peekable();
+    let mut end = 0;
+    let mut theirs = Vec::with_capacity(senders.len());
+    for &(_, nops) in senders {
+        end += nops;
+        let mut these = Vec::new();
+        while values.peek().map_or(false, |&(opi, _)| opi < end) {
+            these.push(values.next().unwrap().1);
+        }
+        theirs.push(these);
+    }
+    theirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use noria::TableOperation;
+
+    fn input(src: Option<usize>, rows: &[i32]) -> Box<Packet> {
+        Box::new(Packet::Input {
+            inner: LocalOrNot::new(Input {
+                dst: unsafe { LocalNodeIndex::make(0) },
+                data: rows
+                    .iter()
+                    .map(|&r| TableOperation::Insert(vec![r.into()]))
+                    .collect(),
+                trace: None,
+            }),
+            src: src.map(|token| SourceChannelIdentifier {
+                token,
+                epoch: 0,
+                tag: 0,
+            }),
+            senders: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn it_attributes_rejections_to_their_senders() {
+        let merged = GroupCommitQueueSet::merge_committed_packets(
+            vec![
+                input(None, &[1, 2]),
+                input(Some(1), &[3]),
+                input(Some(2), &[4, 5]),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        let senders = match *merged {
+            Packet::Input { senders, .. } => senders,
+            _ => unreachable!(),
+        };
+
+        // reject the insert of 3, which is the third operation in the batch, and of 5, and assign
+        // ids to the inserts of 2 and 4
+        let acks = split_replies(
+            senders,
+            vec![(4, vec![5.into()]), (2, vec![3.into()])],
+            vec![(3, 7.into()), (1, 6.into())],
+        );
+        let acks: Vec<_> = acks.into_iter().map(|(src, r)| (src.token, r)).collect();
+        assert_eq!(
+            acks,
+            vec![
+                (
+                    1,
+                    WriteReply {
+                        rejected: vec![vec![3.into()]],
+                        ids: vec![],
+                    }
+                ),
+                (
+                    2,
+                    WriteReply {
+                        rejected: vec![vec![5.into()]],
+                        ids: vec![7.into()],
+                    }
+                )
+            ]
+        );
+    }
+}
@@ -77,12 +77,17 @@ pub enum DurabilityMode {
 pub struct PersistenceParameters {
     /// Force a flush if packets have been in the base table queue for this long.
     pub flush_timeout: time::Duration,
+    /// Force a flush once this many rows have accumulated in a base table queue.
+    pub queue_capacity: usize,
     /// Whether the output files should be deleted when the GroupCommitQueue is dropped.
     pub mode: DurabilityMode,
     /// Filename prefix for persistent log entries.
     pub log_prefix: String,
     /// Absolute path where the log will be written. Defaults to the current directory.
     pub log_dir: Option<PathBuf>,
+    /// Absolute path where `DurabilityMode::Permanent` base tables are stored. Defaults to the
+    /// current directory.
+    pub db_dir: Option<PathBuf>,
     /// Number of background threads PersistentState can use (shared acrosss all worker threads).
     pub persistence_threads: i32,
 }
@@ -91,9 +96,11 @@ impl Default for PersistenceParameters {
     fn default() -> Self {
         Self {
             flush_timeout: time::Duration::new(0, 100_000),
+            queue_capacity: 8192,
             mode: DurabilityMode::MemoryOnly,
             log_prefix: String::from("soup"),
             log_dir: None,
+            db_dir: None,
             persistence_threads: 1,
         }
     }
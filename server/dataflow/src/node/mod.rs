@@ -282,6 +282,13 @@ impl Node {
             _ => HashMap::new(),
         }
     }
+
+    pub fn suggest_ordered_indexes(&self, n: NodeIndex) -> HashMap<NodeIndex, Vec<usize>> {
+        match self.inner {
+            NodeType::Internal(ref i) => i.suggest_ordered_indexes(n),
+            _ => HashMap::new(),
+        }
+    }
 }
 
 impl Deref for Node {
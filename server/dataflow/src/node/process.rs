@@ -32,11 +32,9 @@ impl Node {
             NodeType::Base(ref mut b) => {
                 // NOTE: bases only accept BaseOperations
                 match m.take().map(|p| *p) {
-                    Some(Packet::Input {
-                        inner, mut senders, ..
-                    }) => {
-                        let Input { dst, data } = unsafe { inner.take() };
-                        let mut rs = b.process(addr, data, &*state);
+                    Some(Packet::Input { inner, senders, .. }) => {
+                        let Input { dst, data, trace } = unsafe { inner.take() };
+                        let (mut rs, rejected, ids) = b.process(addr, data, &*state);
 
                         // When a replay originates at a base node, we replay the data *through* that
                         // same base node because its column set may have changed. However, this replay
@@ -50,12 +48,18 @@ impl Node {
                         }
 
                         // Send write-ACKs to all the clients with updates that made
-                        // it into this merged packet:
-                        senders.drain(..).for_each(|src| ex.ack(src));
+                        // it into this merged packet, along with any of their inserts that were
+                        // rejected and any ids that were assigned to them:
+                        for (src, reply) in
+                            crate::group_commit::split_replies(senders, rejected, ids)
+                        {
+                            ex.ack(src, reply);
+                        }
 
                         *m = Some(Box::new(Packet::Message {
                             link: Link::new(dst, dst),
                             data: rs,
+                            trace,
                         }));
                     }
                     Some(ref p) => {
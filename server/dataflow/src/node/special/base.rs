@@ -2,9 +2,71 @@ use crate::prelude::*;
 use noria::{Modification, Operation, TableOperation};
 use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::time;
 use vec_map::VecMap;
 
+/// The maximum number of distinct text values a base node keeps interned.
+const MAX_INTERNED: usize = 1 << 16;
+
+/// Deduplicates the text values written to a base node, so that repeated values (e.g., country
+/// codes) share a single allocation in all downstream records and materializations.
+#[derive(Default)]
+struct Interner {
+    pool: HashSet<DataType>,
+}
+
+impl fmt::Debug for Interner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interner")
+            .field("size", &self.pool.len())
+            .finish()
+    }
+}
+
+impl Interner {
+    fn intern(&mut self, v: &mut DataType) {
+        // tiny texts are stored inline, so there is nothing to share
+        if let DataType::Text(..) = *v {
+            if let Some(shared) = self.pool.get(v) {
+                *v = shared.clone();
+                return;
+            }
+
+            if self.pool.len() >= MAX_INTERNED {
+                // too many distinct values for interning to pay off -- start over
+                self.pool.clear();
+            }
+            self.pool.insert(v.clone());
+        }
+    }
+
+    fn intern_modifications(&mut self, ms: &mut [Modification]) {
+        for m in ms {
+            match *m {
+                Modification::Set(ref mut v) | Modification::Apply(_, ref mut v) => self.intern(v),
+                Modification::None => {}
+            }
+        }
+    }
+
+    fn intern_all(&mut self, op: &mut TableOperation) {
+        match *op {
+            TableOperation::Insert(ref mut row) => row.iter_mut().for_each(|v| self.intern(v)),
+            TableOperation::Delete { .. } => {}
+            TableOperation::Update { ref mut set, .. } => self.intern_modifications(set),
+            TableOperation::InsertOrUpdate {
+                ref mut row,
+                ref mut update,
+            } => {
+                row.iter_mut().for_each(|v| self.intern(v));
+                self.intern_modifications(update);
+            }
+        }
+    }
+}
+
 /// Base is used to represent the root nodes of the Noria data flow graph.
 ///
 /// These nodes perform no computation, and their job is merely to persist all received updates and
@@ -17,6 +79,28 @@ pub struct Base {
     defaults: Vec<DataType>,
     dropped: Vec<usize>,
     unmodified: bool,
+
+    /// Rows whose timestamp in the given column is older than the given duration are expired.
+    expiry: Option<(usize, time::Duration)>,
+
+    /// The timestamp of every stored row along with its primary key (or the whole row, if there
+    /// is no primary key), so that expired rows are found without scanning the base's state.
+    /// Counts how many stored rows have each entry. Rebuilt from state the first time it is needed.
+    #[serde(skip)]
+    expiry_queue: Option<BTreeMap<(DataType, Vec<DataType>), usize>>,
+
+    /// Inserted rows that have no value in these columns get the column's default value instead.
+    declared_defaults: Vec<usize>,
+
+    /// Inserted rows that have no value in this column are assigned the next unused id.
+    auto_increment: Option<usize>,
+
+    /// The next id to hand out. Recomputed from state the first time it is needed.
+    #[serde(skip)]
+    next_id: Option<i128>,
+
+    #[serde(skip)]
+    interner: Interner,
 }
 
 impl Base {
@@ -33,10 +117,51 @@ impl Base {
         self
     }
 
+    /// Builder that expires rows once the timestamp in `column` is more than `ttl` in the past.
+    ///
+    /// Expired rows are retracted through the dataflow, so that views computed from them shrink
+    /// too. Expiry is checked whenever the base processes writes, so rows in a table that receives
+    /// no writes linger until it does.
+    ///
+    /// Panics if `ttl` is too long to subtract from a timestamp.
+    pub fn with_ttl(mut self, column: usize, ttl: time::Duration) -> Base {
+        assert!(
+            chrono::Duration::from_std(ttl).is_ok(),
+            "ttl {:?} is out of range",
+            ttl
+        );
+        self.expiry = Some((column, ttl));
+        self
+    }
+
+    /// Builder that fills in missing values in the given columns of inserted rows with the
+    /// columns' default values.
+    ///
+    /// Since rows cannot tell an omitted value from an explicit `NULL`, this makes it impossible
+    /// to insert `NULL` into those columns.
+    pub fn with_declared_defaults(mut self, columns: Vec<usize>) -> Base {
+        self.declared_defaults = columns;
+        self
+    }
+
+    /// Builder that fills in missing values in `column` of inserted rows with increasing ids.
+    ///
+    /// The ids are handed out by the base itself, so a base with an auto-increment column is
+    /// never sharded.
+    pub fn with_auto_increment(mut self, column: usize) -> Base {
+        self.auto_increment = Some(column);
+        self
+    }
+
     pub fn key(&self) -> Option<&[usize]> {
         self.primary_key.as_ref().map(|cols| &cols[..])
     }
 
+    /// The column this base assigns ids to, if it has one.
+    pub fn auto_increment_column(&self) -> Option<usize> {
+        self.auto_increment
+    }
+
     /// Add a new column to this base node.
     pub fn add_column(&mut self, default: DataType) -> usize {
         assert!(
@@ -95,6 +220,15 @@ impl Clone for Base {
             defaults: self.defaults.clone(),
             dropped: self.dropped.clone(),
             unmodified: self.unmodified,
+
+            expiry: self.expiry,
+            expiry_queue: None,
+
+            declared_defaults: self.declared_defaults.clone(),
+            auto_increment: self.auto_increment,
+            next_id: None,
+
+            interner: Interner::default(),
         }
     }
 }
@@ -107,10 +241,30 @@ impl Default for Base {
             defaults: Vec::new(),
             dropped: Vec::new(),
             unmodified: true,
+
+            expiry: None,
+            expiry_queue: None,
+
+            declared_defaults: Vec::new(),
+            auto_increment: None,
+            next_id: None,
+
+            interner: Interner::default(),
         }
     }
 }
 
+/// The value of an auto-increment column as an id, if it is an integer.
+fn id_of(v: &DataType) -> Option<i128> {
+    match *v {
+        DataType::Int(_)
+        | DataType::UnsignedInt(_)
+        | DataType::BigInt(_)
+        | DataType::UnsignedBigInt(_) => Some(i128::from(v)),
+        _ => None,
+    }
+}
+
 fn key_val(i: usize, col: usize, r: &TableOperation) -> &DataType {
     match *r {
         TableOperation::Insert(ref row) => &row[col],
@@ -132,14 +286,187 @@ impl Base {
         Clone::clone(self)
     }
 
+    /// Apply a batch of writes to this base, and produce the resulting records.
+    ///
+    /// Inserts whose key is already present, either in the base's state or earlier in the batch,
+    /// are rejected. The position and key of each such insert in `ops` is returned alongside the
+    /// records, as is the position of each insert that was assigned an auto-increment id, along
+    /// with that id.
     pub(in crate::node) fn process(
         &mut self,
         us: LocalNodeIndex,
         mut ops: Vec<TableOperation>,
         state: &StateMap,
+    ) -> (Records, Vec<(usize, Vec<DataType>)>, Vec<(usize, DataType)>) {
+        for op in &mut ops {
+            self.interner.intern_all(op);
+        }
+
+        if !self.declared_defaults.is_empty() {
+            self.fill_defaults(&mut ops);
+        }
+
+        let ids = match self.auto_increment {
+            Some(column) => self.assign_ids(us, column, &mut ops, state),
+            None => Vec::new(),
+        };
+
+        let (records, rejected) = self.apply(us, ops, state);
+        let records = match self.expiry {
+            Some((column, ttl)) => self.expire(us, column, ttl, records, state),
+            None => records,
+        };
+        (records, rejected, ids)
+    }
+
+    /// Fills in the declared default of any column that inserted rows left empty.
+    fn fill_defaults(&self, ops: &mut [TableOperation]) {
+        for op in ops {
+            let row = match *op {
+                TableOperation::Insert(ref mut row)
+                | TableOperation::InsertOrUpdate { ref mut row, .. } => row,
+                _ => continue,
+            };
+
+            for &col in &self.declared_defaults {
+                if let Some(v) = row.get_mut(col) {
+                    if v.is_none() {
+                        *v = self.defaults[col].clone();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fills in the auto-increment column of any inserted rows that did not provide a value, and
+    /// returns the position of each such row in `ops` along with the id it was given.
+    ///
+    /// Values in the column that are not integers are left alone, and are never considered when
+    /// picking the next id.
+    fn assign_ids(
+        &mut self,
+        us: LocalNodeIndex,
+        column: usize,
+        ops: &mut [TableOperation],
+        state: &StateMap,
+    ) -> Vec<(usize, DataType)> {
+        let next_id = self.next_id.get_or_insert_with(|| {
+            state
+                .get(us)
+                .and_then(|db| {
+                    db.cloned_records()
+                        .iter()
+                        .filter_map(|r| id_of(&r[column]))
+                        .max()
+                })
+                .map(|max| max + 1)
+                .unwrap_or(1)
+        });
+
+        let mut ids = Vec::new();
+        for (opi, op) in ops.iter_mut().enumerate() {
+            let row = match *op {
+                TableOperation::Insert(ref mut row)
+                | TableOperation::InsertOrUpdate { ref mut row, .. } => row,
+                _ => continue,
+            };
+
+            match row.get_mut(column) {
+                Some(v) if v.is_none() => {
+                    *v = DataType::from(*next_id as i64);
+                    *next_id += 1;
+                    ids.push((opi, v.clone()));
+                }
+                Some(v) => {
+                    // don't hand out ids that the client has already used
+                    if let Some(id) = id_of(v) {
+                        *next_id = (*next_id).max(id + 1);
+                    }
+                }
+                None => {}
+            }
+        }
+        ids
+    }
+
+    /// Drops new rows that have already expired, and retracts any stored rows that have.
+    fn expire(
+        &mut self,
+        us: LocalNodeIndex,
+        column: usize,
+        ttl: time::Duration,
+        mut records: Records,
+        state: &StateMap,
     ) -> Records {
+        let ttl = chrono::Duration::from_std(ttl).expect("ttl is checked by with_ttl");
+        let cutoff = DataType::from(chrono::Local::now().naive_local() - ttl);
+        records.retain(|r| !r.is_positive() || r[column] >= cutoff);
+
+        let db = state
+            .get(us)
+            .expect("base with expiry must be materialized");
+        let key_cols = self.primary_key.as_ref();
+        let entry = |row: &[DataType]| -> (DataType, Vec<DataType>) {
+            let id = match key_cols {
+                Some(cols) => cols.iter().map(|&col| row[col].clone()).collect(),
+                None => row.to_vec(),
+            };
+            (row[column].clone(), id)
+        };
+
+        let queue = self.expiry_queue.get_or_insert_with(|| {
+            let mut queue = BTreeMap::new();
+            for row in db.cloned_records() {
+                *queue.entry(entry(&row)).or_insert(0) += 1;
+            }
+            queue
+        });
+
+        // keep the queue in line with the rows this batch stores and removes, so that rows this
+        // batch has retracted already aren't retracted again below
+        for r in records.iter() {
+            let e = entry(r.rec());
+            if r.is_positive() {
+                *queue.entry(e).or_insert(0) += 1;
+            } else if let Some(n) = queue.get_mut(&e) {
+                *n -= 1;
+                if *n == 0 {
+                    queue.remove(&e);
+                }
+            }
+        }
+
+        // everything below the cutoff is stored, and untouched by this batch, since the batch's
+        // own expired rows were dropped above
+        let expired: Vec<_> = queue
+            .range(..(cutoff, Vec::new()))
+            .map(|(e, &n)| (e.clone(), n))
+            .collect();
+        for ((ts, id), n) in expired {
+            queue.remove(&(ts, id.clone()));
+            match key_cols {
+                Some(cols) => match db.lookup(cols, &KeyType::from(&id[..])) {
+                    LookupResult::Some(rows) => records.extend(
+                        rows.into_iter()
+                            .map(|row| Record::Negative(row.into_owned())),
+                    ),
+                    LookupResult::Missing => unreachable!("base state is never partial"),
+                },
+                None => records.extend((0..n).map(|_| Record::Negative(id.clone()))),
+            }
+        }
+
+        records
+    }
+
+    fn apply(
+        &mut self,
+        us: LocalNodeIndex,
+        ops: Vec<TableOperation>,
+        state: &StateMap,
+    ) -> (Records, Vec<(usize, Vec<DataType>)>) {
         if self.primary_key.is_none() || ops.is_empty() {
-            return ops
+            let records = ops
                 .into_iter()
                 .map(|r| {
                     if let TableOperation::Insert(mut r) = r {
@@ -150,13 +477,16 @@ impl Base {
                     }
                 })
                 .collect();
+            return (records, Vec::new());
         }
 
         let key_cols = &self.primary_key.as_ref().unwrap()[..];
-        ops.sort_by(|a, b| key_of(key_cols, a).cmp(key_of(key_cols, b)));
+        // remember where each operation came from, so that rejections can be traced back to it
+        let mut ops: Vec<_> = ops.into_iter().enumerate().collect();
+        ops.sort_by(|(_, a), (_, b)| key_of(key_cols, a).cmp(key_of(key_cols, b)));
 
         // starting key
-        let mut this_key: Vec<_> = key_of(key_cols, &ops[0]).cloned().collect();
+        let mut this_key: Vec<_> = key_of(key_cols, &ops[0].1).cloned().collect();
 
         // starting record state
         let db = state
@@ -183,7 +513,8 @@ impl Base {
         let mut was = current.clone();
 
         let mut results = Vec::with_capacity(ops.len());
-        for op in ops {
+        let mut rejected = Vec::new();
+        for (opi, op) in ops {
             if this_key.iter().cmp(key_of(key_cols, &op)) != Ordering::Equal {
                 if current != was {
                     if let Some(was) = was {
@@ -201,10 +532,10 @@ impl Base {
 
             let update = match op {
                 TableOperation::Insert(row) => {
-                    if let Some(ref was) = was {
-                        eprintln!("base ignoring {:?} since it already has {:?}", row, was);
+                    if current.is_some() {
+                        // the key is taken, either in our state or earlier in this batch
+                        rejected.push((opi, this_key.clone()));
                     } else {
-                        //assert!(was.is_none());
                         current = Some(Cow::Owned(row));
                     }
                     continue;
@@ -267,7 +598,7 @@ impl Base {
             self.fix(r);
         }
 
-        results.into()
+        (results.into(), rejected)
     }
 
     pub(in crate::node) fn suggest_indexes(&self, n: NodeIndex) -> HashMap<NodeIndex, Vec<usize>> {
@@ -307,7 +638,36 @@ mod tests {
         assert_eq!(b.unmodified, true);
     }
 
-    fn test_lots_of_changes_in_same_batch(mut state: Box<dyn State>) {
+    #[test]
+    fn it_interns_text() {
+        let ptr = |v: &DataType| match *v {
+            DataType::Text(ref t) => t.to_bytes().as_ptr(),
+            _ => unreachable!(),
+        };
+
+        let mut interner = Interner::default();
+        let mut a: DataType = "a rather long text value".into();
+        let mut b: DataType = "a rather long text value".into();
+        let mut c: DataType = "another rather long text value".into();
+        assert_ne!(ptr(&a), ptr(&b));
+
+        interner.intern(&mut a);
+        interner.intern(&mut b);
+        interner.intern(&mut c);
+        assert_eq!(ptr(&a), ptr(&b));
+        assert_ne!(ptr(&a), ptr(&c));
+        assert_eq!(interner.pool.len(), 2);
+
+        // short strings are never pooled
+        let mut tiny: DataType = "US".into();
+        interner.intern(&mut tiny);
+        assert_eq!(interner.pool.len(), 2);
+    }
+
+    /// What processing a batch of writes at a base produces.
+    type Processed = (Records, Vec<(usize, Vec<DataType>)>, Vec<(usize, DataType)>);
+
+    fn setup(b: Base, mut state: Box<dyn State>) -> impl FnMut(Vec<TableOperation>) -> Processed {
         use crate::node;
         use crate::prelude::*;
 
@@ -319,7 +679,6 @@ mod tests {
             node::NodeType::Source,
         ));
 
-        let b = Base::new(vec![]).with_key(vec![0, 2]);
         let global = graph.add_node(Node::new("b", &["x", "y", "z"], b));
         graph.add_edge(source, global, ());
         let local = unsafe { LocalNodeIndex::make(0 as u32) };
@@ -344,11 +703,16 @@ mod tests {
         let n = graph[global].take();
         let mut n = n.finalize(&graph);
 
-        let mut one = move |u: Vec<TableOperation>| {
-            let mut m = n.get_base_mut().unwrap().process(local, u, &states);
+        move |u: Vec<TableOperation>| {
+            let (mut m, rejected, ids) = n.get_base_mut().unwrap().process(local, u, &states);
             node::materialize(&mut m, None, states.get_mut(local));
-            m
-        };
+            (m, rejected, ids)
+        }
+    }
+
+    fn test_lots_of_changes_in_same_batch(state: Box<dyn State>) {
+        let b = Base::new(vec![]).with_key(vec![0, 2]);
+        let mut one = setup(b, state);
 
         assert_eq!(
             one(vec![
@@ -397,8 +761,190 @@ mod tests {
                     key: vec![2.into(), 1.into()],
                 },
             ]),
-            Records::default()
+            (Records::default(), vec![], vec![])
+        );
+    }
+
+    fn test_expires_rows(state: Box<dyn State>) {
+        use std::thread;
+
+        let now = || DataType::from(chrono::Local::now().naive_local());
+        let ttl = time::Duration::from_millis(200);
+        let b = Base::new(vec![]).with_key(vec![0]).with_ttl(1, ttl);
+        let mut one = setup(b, state);
+
+        // rows that have already expired are never stored
+        let old: DataType =
+            (chrono::Local::now().naive_local() - chrono::Duration::hours(1)).into();
+        let fresh = vec![1.into(), now(), 1.into()];
+        let (records, _, _) = one(vec![
+            TableOperation::Insert(vec![0.into(), old, 0.into()]),
+            TableOperation::Insert(fresh.clone()),
+        ]);
+        assert_eq!(records, vec![Record::Positive(fresh.clone())].into());
+
+        thread::sleep(ttl * 2);
+        let newer = vec![2.into(), now(), 2.into()];
+        let (records, _, _) = one(vec![TableOperation::Insert(newer.clone())]);
+        assert_eq!(
+            records,
+            vec![
+                Record::Positive(newer.clone()),
+                Record::Negative(fresh.clone())
+            ]
+            .into()
+        );
+
+        // expired rows that are also deleted by a write are only retracted once
+        thread::sleep(ttl * 2);
+        let (records, _, _) = one(vec![TableOperation::Delete {
+            key: vec![2.into()],
+        }]);
+        assert_eq!(records, vec![Record::Negative(newer)].into());
+    }
+
+    #[test]
+    fn it_expires_rows() {
+        test_expires_rows(Box::new(MemoryState::default()))
+    }
+
+    #[test]
+    fn it_expires_rows_persistent() {
+        let state = PersistentState::new(
+            String::from("it_expires_rows_persistent"),
+            Some(&[0][..]),
+            &PersistenceParameters::default(),
+        );
+
+        test_expires_rows(Box::new(state))
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn it_rejects_overlong_ttls() {
+        Base::new(vec![]).with_ttl(1, time::Duration::from_secs(u64::max_value()));
+    }
+
+    #[test]
+    fn it_assigns_auto_increment_ids() {
+        let b = Base::new(vec![]).with_key(vec![0]).with_auto_increment(0);
+        let mut one = setup(b, Box::new(MemoryState::default()));
+
+        let (records, _, ids) = one(vec![
+            TableOperation::Insert(vec![DataType::None, "a".into(), 1.into()]),
+            TableOperation::Insert(vec![DataType::None, "b".into(), 2.into()]),
+        ]);
+        assert_eq!(
+            records,
+            vec![
+                Record::Positive(vec![1.into(), "a".into(), 1.into()]),
+                Record::Positive(vec![2.into(), "b".into(), 2.into()]),
+            ]
+            .into()
+        );
+        let expected: Vec<(usize, DataType)> = vec![(0, 1.into()), (1, 2.into())];
+        assert_eq!(ids, expected);
+
+        // explicitly provided ids are kept, and never handed out again
+        let (records, _, ids) = one(vec![
+            TableOperation::Insert(vec![10.into(), "c".into(), 3.into()]),
+            TableOperation::Insert(vec![DataType::None, "d".into(), 4.into()]),
+        ]);
+        assert_eq!(
+            records,
+            vec![
+                Record::Positive(vec![10.into(), "c".into(), 3.into()]),
+                Record::Positive(vec![11.into(), "d".into(), 4.into()]),
+            ]
+            .into()
+        );
+        let expected: Vec<(usize, DataType)> = vec![(1, 11.into())];
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn it_ignores_non_integer_ids() {
+        let b = Base::new(vec![]).with_key(vec![0]).with_auto_increment(0);
+        let mut one = setup(b, Box::new(MemoryState::default()));
+
+        // ids that are not integers don't stop the base from picking the next one
+        let x = vec!["x".into(), "a".into(), 1.into()];
+        let (records, _, _) = one(vec![TableOperation::Insert(x.clone())]);
+        assert_eq!(records, vec![Record::Positive(x)].into());
+        let y = vec![1.5.into(), "b".into(), 2.into()];
+        let (records, _, ids) = one(vec![TableOperation::Insert(y.clone())]);
+        assert_eq!(records, vec![Record::Positive(y)].into());
+        assert!(ids.is_empty());
+
+        let (records, _, ids) = one(vec![TableOperation::Insert(vec![
+            DataType::None,
+            "c".into(),
+            3.into(),
+        ])]);
+        assert_eq!(
+            records,
+            vec![Record::Positive(vec![1.into(), "c".into(), 3.into()])].into()
+        );
+        let expected: Vec<(usize, DataType)> = vec![(0, 1.into())];
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn it_fills_in_declared_defaults() {
+        let b = Base::new(vec![DataType::None, "none".into(), 0.into()])
+            .with_key(vec![0])
+            .with_declared_defaults(vec![1]);
+        let mut one = setup(b, Box::new(MemoryState::default()));
+
+        // only columns with a declared default are filled in
+        let (records, _, _) = one(vec![
+            TableOperation::Insert(vec![1.into(), DataType::None, DataType::None]),
+            TableOperation::Insert(vec![2.into(), "two".into(), 2.into()]),
+        ]);
+        assert_eq!(
+            records,
+            vec![
+                Record::Positive(vec![1.into(), "none".into(), DataType::None]),
+                Record::Positive(vec![2.into(), "two".into(), 2.into()]),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn it_rejects_duplicate_keys() {
+        let b = Base::new(vec![]).with_key(vec![0]);
+        let mut one = setup(b, Box::new(MemoryState::default()));
+
+        let a = vec![1.into(), "a".into(), 1.into()];
+        let (records, rejected, _) = one(vec![TableOperation::Insert(a.clone())]);
+        assert_eq!(records, vec![Record::Positive(a.clone())].into());
+        assert!(rejected.is_empty());
+
+        // both a stored row and an earlier insert in the same batch hold on to their key
+        let b = vec![2.into(), "b".into(), 2.into()];
+        let (records, rejected, _) = one(vec![
+            TableOperation::Insert(vec![1.into(), "x".into(), 1.into()]),
+            TableOperation::Insert(b.clone()),
+            TableOperation::Insert(vec![2.into(), "y".into(), 2.into()]),
+        ]);
+        assert_eq!(records, vec![Record::Positive(b)].into());
+        let expected: Vec<(usize, Vec<DataType>)> = vec![(0, vec![1.into()]), (2, vec![2.into()])];
+        assert_eq!(rejected, expected);
+
+        // but a key is free again once its row has been deleted
+        let c = vec![1.into(), "c".into(), 1.into()];
+        let (records, rejected, _) = one(vec![
+            TableOperation::Delete {
+                key: vec![1.into()],
+            },
+            TableOperation::Insert(c.clone()),
+        ]);
+        assert_eq!(
+            records,
+            vec![Record::Negative(a), Record::Positive(c)].into()
         );
+        assert!(rejected.is_empty());
     }
 
     #[test]
@@ -58,6 +58,11 @@ impl Egress {
             ref tags,
         } = self;
 
+        // don't make downstream domains process no-op pairs of updates
+        if m.as_ref().unwrap().is_regular() {
+            m.as_mut().unwrap().map_data(Records::coalesce);
+        }
+
         // send any queued updates to all external children
         assert!(!txs.is_empty());
         let txn = txs.len() - 1;
@@ -1,5 +1,7 @@
 use crate::backlog;
+use crate::ops::filter::FilterCondition;
 use crate::prelude::*;
+use std::sync::Arc;
 
 #[derive(Serialize, Deserialize)]
 pub struct Reader {
@@ -8,6 +10,8 @@ pub struct Reader {
 
     for_node: NodeIndex,
     state: Option<Vec<usize>>,
+    order: Option<usize>,
+    filter: Option<Arc<Vec<(usize, FilterCondition)>>>,
 }
 
 impl Clone for Reader {
@@ -16,6 +20,8 @@ impl Clone for Reader {
         Reader {
             writer: None,
             state: self.state.clone(),
+            order: self.order,
+            filter: self.filter.clone(),
             for_node: self.for_node,
         }
     }
@@ -26,6 +32,8 @@ impl Reader {
         Reader {
             writer: None,
             state: None,
+            order: None,
+            filter: None,
             for_node,
         }
     }
@@ -49,6 +57,8 @@ impl Reader {
         Self {
             writer: self.writer.take(),
             state: self.state.clone(),
+            order: self.order,
+            filter: self.filter.clone(),
             for_node: self.for_node,
         }
     }
@@ -81,22 +91,53 @@ impl Reader {
         }
     }
 
+    /// The column by which the rows for each key are kept ordered, if any.
+    pub fn order(&self) -> Option<usize> {
+        self.order
+    }
+
+    pub fn set_order(&mut self, column: usize) {
+        assert!(
+            self.writer.is_none(),
+            "cannot change the order of an already materialized reader"
+        );
+        self.order = Some(column);
+    }
+
+    /// The conditions that rows must match to be returned by reads, if any.
+    pub fn filter(&self) -> Option<&Arc<Vec<(usize, FilterCondition)>>> {
+        self.filter.as_ref()
+    }
+
+    pub fn set_filter(&mut self, filter: &[(usize, FilterCondition)]) {
+        assert!(
+            self.writer.is_none(),
+            "cannot change the filter of an already materialized reader"
+        );
+        self.filter = Some(Arc::new(Vec::from(filter)));
+    }
+
     pub(crate) fn is_empty(&self) -> bool {
         self.writer.as_ref().map(|w| w.is_empty()).unwrap_or(true)
     }
 
+    pub(crate) fn read_latency(&self) -> Option<noria::debug::stats::ReadLatency> {
+        self.writer
+            .as_ref()
+            .and_then(backlog::WriteHandle::read_latency)
+    }
+
     pub(crate) fn state_size(&self) -> Option<u64> {
         self.writer.as_ref().map(SizeOf::deep_size_of)
     }
 
-    /// Evict a randomly selected key, returning the number of bytes evicted.
-    /// Note that due to how `evmap` applies the evictions asynchronously, we can only evict a
-    /// single key at a time here.
-    pub(crate) fn evict_random_keys(&mut self, n: usize) -> u64 {
+    /// Evict up to `n` keys that have not been read recently, returning the number of bytes
+    /// evicted.
+    pub(crate) fn evict_least_recent_keys(&mut self, n: usize) -> u64 {
         let mut bytes_freed = 0;
         if let Some(ref mut handle) = self.writer {
             let mut rng = rand::thread_rng();
-            bytes_freed = handle.evict_random_keys(&mut rng, n);
+            bytes_freed = handle.evict_least_recent_keys(&mut rng, n);
             handle.swap();
         }
         bytes_freed
@@ -17,6 +17,33 @@ pub struct Filter {
 pub enum Value {
     Constant(DataType),
     Column(usize),
+    /// The given argument of a read from a view (see `View::lookup_with_args`).
+    ///
+    /// Only conditions that are applied by a reader (see `Migration::filter_reader_by`) may use
+    /// these.
+    Argument(usize),
+}
+
+impl Value {
+    /// Resolves this value for the row `r`, given the arguments `args` of the current read.
+    fn resolve<'a>(&'a self, r: &'a [DataType], args: &'a [DataType]) -> Cow<'a, DataType> {
+        match *self {
+            Value::Constant(ref dt) => Cow::Borrowed(dt),
+            Value::Column(c) => Cow::Borrowed(&r[c]),
+            // a missing argument is unknown, just like NULL
+            Value::Argument(a) => args
+                .get(a)
+                .map(Cow::Borrowed)
+                .unwrap_or(Cow::Owned(DataType::None)),
+        }
+    }
+
+    fn is_argument(&self) -> bool {
+        match *self {
+            Value::Argument(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl From<DataType> for Value {
@@ -30,6 +57,7 @@ impl Display for Value {
         match *self {
             Value::Constant(ref c) => write!(f, "{}", c),
             Value::Column(ref ci) => write!(f, "col: {}", ci),
+            Value::Argument(ref ai) => write!(f, "arg: {}", ai),
         }
     }
 }
@@ -38,6 +66,208 @@ impl Display for Value {
 pub enum FilterCondition {
     Comparison(Operator, Value),
     In(Vec<DataType>),
+    /// Matches values between the two bounds (inclusive), i.e., `lower <= x AND x <= upper`.
+    Between(Value, Value),
+    /// Compares the great-circle distance (in kilometers) between the position given by the
+    /// filtered column (latitude) and the `lon` column (longitude), and the point `from`, against
+    /// `radius` using `op`.
+    ///
+    /// The coordinates of `from` are usually read arguments, so that a reader filtered by this
+    /// condition can be asked for the rows near any point.
+    Distance {
+        lon: usize,
+        from: (Value, Value),
+        op: Operator,
+        radius: f64,
+    },
+    /// Matches if all of the given conditions (each on its own column) match.
+    And(Vec<(usize, FilterCondition)>),
+    /// Matches if any of the given conditions (each on its own column) match.
+    Or(Vec<(usize, FilterCondition)>),
+    /// Matches if the given condition (on the same column) does not.
+    Not(Box<FilterCondition>),
+}
+
+/// Mean radius of the Earth in kilometers.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Computes the great-circle distance in kilometers between two (latitude, longitude) positions
+/// given in degrees using the haversine formula.
+pub fn haversine_distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+fn compare<T: PartialOrd>(op: &Operator, d: &T, v: &T) -> bool {
+    match *op {
+        Operator::Equal => d == v,
+        Operator::NotEqual => d != v,
+        Operator::Greater => d > v,
+        Operator::GreaterOrEqual => d >= v,
+        Operator::Less => d < v,
+        Operator::LessOrEqual => d <= v,
+        Operator::In => unreachable!(),
+        _ => unimplemented!(),
+    }
+}
+
+impl FilterCondition {
+    /// Returns true if the value `d` in the row `r` satisfies this condition.
+    pub fn matches(&self, d: &DataType, r: &[DataType]) -> bool {
+        self.matches_with_args(d, r, &[])
+    }
+
+    /// Returns true if the value `d` in the row `r` satisfies this condition, given the arguments
+    /// `args` of the current read.
+    pub fn matches_with_args(&self, d: &DataType, r: &[DataType], args: &[DataType]) -> bool {
+        self.eval(d, r, args) == Some(true)
+    }
+
+    /// Returns true if this condition refers to the arguments of a read, and so can only be
+    /// applied by a reader.
+    pub fn takes_arguments(&self) -> bool {
+        match *self {
+            FilterCondition::Comparison(_, ref v) => v.is_argument(),
+            FilterCondition::In(_) => false,
+            FilterCondition::Between(ref lower, ref upper) => {
+                lower.is_argument() || upper.is_argument()
+            }
+            FilterCondition::Distance { ref from, .. } => {
+                from.0.is_argument() || from.1.is_argument()
+            }
+            FilterCondition::And(ref conds) | FilterCondition::Or(ref conds) => {
+                conds.iter().any(|(_, cond)| cond.takes_arguments())
+            }
+            FilterCondition::Not(ref cond) => cond.takes_arguments(),
+        }
+    }
+
+    /// Evaluates this condition for the value `d` in the row `r` using SQL's three-valued logic,
+    /// where `None` means that the result is unknown (because NULLs were involved).
+    fn eval(&self, d: &DataType, r: &[DataType], args: &[DataType]) -> Option<bool> {
+        match *self {
+            // comparisons against a NULL literal are `IS NULL` and `IS NOT NULL`
+            FilterCondition::Comparison(ref op, Value::Constant(DataType::None)) => match *op {
+                Operator::Equal => Some(d.is_none()),
+                Operator::NotEqual => Some(!d.is_none()),
+                _ => None,
+            },
+            FilterCondition::Comparison(ref op, ref f) => {
+                let v = f.resolve(r, args);
+                // any other comparison involving NULL is unknown, and so does not match
+                if d.is_none() || v.is_none() {
+                    None
+                } else {
+                    Some(compare(op, d, &*v))
+                }
+            }
+            FilterCondition::In(ref fs) => {
+                if d.is_none() {
+                    None
+                } else if fs.contains(d) {
+                    Some(true)
+                } else if fs.iter().any(DataType::is_none) {
+                    // the value might have been equal to the NULL, so we can't tell
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            FilterCondition::Between(ref lower, ref upper) => {
+                let (lower, upper) = (lower.resolve(r, args), upper.resolve(r, args));
+                if d.is_none() || lower.is_none() || upper.is_none() {
+                    None
+                } else {
+                    Some(&*lower <= d && d <= &*upper)
+                }
+            }
+            FilterCondition::Distance {
+                lon,
+                ref from,
+                ref op,
+                radius,
+            } => {
+                let from = (
+                    from.0.resolve(r, args).to_f64(),
+                    from.1.resolve(r, args).to_f64(),
+                );
+                match (d.to_f64(), r[lon].to_f64(), from) {
+                    (Some(lat), Some(lon), (Some(from_lat), Some(from_lon))) => {
+                        let distance = haversine_distance((lat, lon), (from_lat, from_lon));
+                        Some(compare(op, &distance, &radius))
+                    }
+                    // a missing (or non-numeric) position is never near anything
+                    _ => None,
+                }
+            }
+            FilterCondition::And(ref conds) => {
+                // false if any condition is false, otherwise unknown if any is unknown
+                let mut result = Some(true);
+                for (i, cond) in conds {
+                    match cond.eval(&r[*i], r, args) {
+                        Some(false) => return Some(false),
+                        None => result = None,
+                        Some(true) => {}
+                    }
+                }
+                result
+            }
+            FilterCondition::Or(ref conds) => {
+                // true if any condition is true, otherwise unknown if any is unknown
+                let mut result = Some(false);
+                for (i, cond) in conds {
+                    match cond.eval(&r[*i], r, args) {
+                        Some(true) => return Some(true),
+                        None => result = None,
+                        Some(false) => {}
+                    }
+                }
+                result
+            }
+            FilterCondition::Not(ref cond) => cond.eval(d, r, args).map(|b| !b),
+        }
+    }
+
+    /// Produces a human-readable description of this condition applied to column `col`.
+    pub fn describe(&self, col: usize) -> String {
+        let join = |conds: &[(usize, FilterCondition)], sep: &str| {
+            conds
+                .iter()
+                .map(|(i, cond)| cond.describe(*i))
+                .collect::<Vec<_>>()
+                .join(sep)
+        };
+
+        match *self {
+            FilterCondition::Comparison(ref op, ref x) => format!("f{} {} {}", col, op, x),
+            FilterCondition::In(ref xs) => format!(
+                "f{} IN ({})",
+                col,
+                xs.iter()
+                    .map(|d| format!("{}", d))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            FilterCondition::Between(ref lower, ref upper) => {
+                format!("f{} BETWEEN {} AND {}", col, lower, upper)
+            }
+            FilterCondition::Distance {
+                lon,
+                ref from,
+                ref op,
+                radius,
+            } => format!(
+                "dist(f{}, f{}, ({}, {})) {} {}",
+                col, lon, from.0, from.1, op, radius
+            ),
+            FilterCondition::And(ref conds) => format!("({})", join(conds, " AND ")),
+            FilterCondition::Or(ref conds) => format!("({})", join(conds, " OR ")),
+            FilterCondition::Not(ref cond) => format!("NOT {}", cond.describe(col)),
+        }
+    }
 }
 
 impl Filter {
@@ -66,6 +296,10 @@ impl Ingredient for Filter {
         // N.B.: <= because the adjacent node might be a base with a suffix of removed columns.
         // It's okay to just ignore those.
         assert!(self.filter.len() <= srcn.fields().len());
+        assert!(
+            !self.filter.iter().any(|(_, cond)| cond.takes_arguments()),
+            "conditions on read arguments must be applied by a reader"
+        );
     }
 
     fn on_commit(&mut self, _: NodeIndex, remap: &HashMap<NodeIndex, IndexPair>) {
@@ -81,31 +315,7 @@ impl Ingredient for Filter {
         _: &DomainNodes,
         _: &StateMap,
     ) -> ProcessingResult {
-        rs.retain(|r| {
-            self.filter.iter().all(|(i, cond)| {
-                // check if this filter matches
-                let d = &r[*i];
-                match cond {
-                    FilterCondition::Comparison(ref op, ref f) => {
-                        let v = match *f {
-                            Value::Constant(ref dt) => dt,
-                            Value::Column(c) => &r[c],
-                        };
-                        match *op {
-                            Operator::Equal => d == v,
-                            Operator::NotEqual => d != v,
-                            Operator::Greater => d > v,
-                            Operator::GreaterOrEqual => d >= v,
-                            Operator::Less => d < v,
-                            Operator::LessOrEqual => d <= v,
-                            Operator::In => unreachable!(),
-                            _ => unimplemented!(),
-                        }
-                    }
-                    FilterCondition::In(ref fs) => fs.contains(d),
-                }
-            })
-        });
+        rs.retain(|r| self.filter.iter().all(|(i, cond)| cond.matches(&r[*i], r)));
 
         ProcessingResult {
             results: rs,
@@ -150,6 +360,11 @@ impl Ingredient for Filter {
                             .collect::<Vec<_>>()
                             .join(", ")
                     )),
+                    FilterCondition::Distance { .. }
+                    | FilterCondition::Between(..)
+                    | FilterCondition::And(..)
+                    | FilterCondition::Or(..)
+                    | FilterCondition::Not(..) => Some(escape(&cond.describe(*i))),
                 })
                 .collect::<Vec<_>>()
                 .as_slice()
@@ -172,30 +387,8 @@ impl Ingredient for Filter {
         self.lookup(*self.src, columns, key, nodes, states)
             .and_then(|result| {
                 let f = self.filter.clone();
-                let filter = move |r: &[DataType]| {
-                    f.iter().all(|(i, ref cond)| {
-                        // check if this filter matches
-                        let d = &r[*i];
-                        match *cond {
-                            FilterCondition::Comparison(ref op, ref f) => {
-                                let v = match *f {
-                                    Value::Constant(ref dt) => dt,
-                                    Value::Column(c) => &r[c],
-                                };
-                                match *op {
-                                    Operator::Equal => d == v,
-                                    Operator::NotEqual => d != v,
-                                    Operator::Greater => d > v,
-                                    Operator::GreaterOrEqual => d >= v,
-                                    Operator::Less => d < v,
-                                    Operator::LessOrEqual => d <= v,
-                                    _ => unimplemented!(),
-                                }
-                            }
-                            FilterCondition::In(ref fs) => fs.contains(d),
-                        }
-                    })
-                };
+                let filter =
+                    move |r: &[DataType]| f.iter().all(|(i, ref cond)| cond.matches(&r[*i], r));
 
                 match result {
                     Some(rs) => {
@@ -306,6 +499,27 @@ mod tests {
         assert!(g.narrow_one_row(left.clone(), false).is_empty());
     }
 
+    #[test]
+    fn it_is_delta_correct() {
+        use rand::Rng;
+
+        let mut g = setup(false, None);
+        g.check_deltas(
+            0xf17e,
+            200,
+            |rng| {
+                let y = ["a", "b", "c"][rng.gen_range(0, 3)];
+                vec![rng.gen_range(0i32, 5).into(), y.into()]
+            },
+            |rows| {
+                rows.iter()
+                    .filter(|r| r[1] == DataType::from("a"))
+                    .cloned()
+                    .collect()
+            },
+        );
+    }
+
     #[test]
     fn it_suggests_indices() {
         let g = setup(false, None);
@@ -420,4 +634,256 @@ mod tests {
         left = vec![42.into(), "b".into()];
         assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
     }
+
+    #[test]
+    fn it_drops_null_comparisons() {
+        let mut g = setup(
+            false,
+            Some(&[(
+                0,
+                FilterCondition::Comparison(Operator::NotEqual, Value::Column(1)),
+            )]),
+        );
+
+        let mut left: Vec<DataType>;
+        left = vec![1.into(), 2.into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![DataType::None, 2.into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+        left = vec![1.into(), DataType::None];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+
+        let mut g = setup(
+            false,
+            Some(&[(0, FilterCondition::In(vec![DataType::None, 1.into()]))]),
+        );
+        left = vec![1.into(), "a".into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![DataType::None, "a".into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+
+        // a miss in a list with a NULL is unknown, so negating it doesn't match either
+        let mut g = setup(
+            false,
+            Some(&[(
+                0,
+                FilterCondition::Not(Box::new(FilterCondition::In(vec![
+                    DataType::None,
+                    1.into(),
+                ]))),
+            )]),
+        );
+        left = vec![1.into(), "a".into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+        left = vec![2.into(), "a".into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+
+        let mut g = setup(
+            false,
+            Some(&[(
+                0,
+                FilterCondition::Not(Box::new(FilterCondition::In(vec![1.into()]))),
+            )]),
+        );
+        left = vec![2.into(), "a".into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+    }
+
+    #[test]
+    fn it_works_with_is_null() {
+        let mut g = setup(
+            false,
+            Some(&[(
+                1,
+                FilterCondition::Comparison(Operator::Equal, Value::Constant(DataType::None)),
+            )]),
+        );
+
+        let mut left: Vec<DataType>;
+        left = vec![1.into(), DataType::None];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![1.into(), "a".into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+
+        let mut g = setup(
+            false,
+            Some(&[(
+                1,
+                FilterCondition::Comparison(Operator::NotEqual, Value::Constant(DataType::None)),
+            )]),
+        );
+        left = vec![1.into(), "a".into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![1.into(), DataType::None];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+    }
+
+    #[test]
+    fn it_works_with_between() {
+        let mut g = setup(
+            false,
+            Some(&[(
+                0,
+                FilterCondition::Between(Value::Constant(2.into()), Value::Column(1)),
+            )]),
+        );
+
+        let mut left: Vec<DataType>;
+        left = vec![2.into(), 4.into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![4.into(), 4.into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![1.into(), 4.into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+        left = vec![5.into(), 4.into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+        left = vec![3.into(), DataType::None];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+    }
+
+    #[test]
+    fn it_works_with_logical_ops() {
+        // x = 1 OR NOT y = "a"
+        let mut g = setup(
+            false,
+            Some(&[(
+                0,
+                FilterCondition::Or(vec![
+                    (
+                        0,
+                        FilterCondition::Comparison(Operator::Equal, Value::Constant(1.into())),
+                    ),
+                    (
+                        1,
+                        FilterCondition::Not(Box::new(FilterCondition::Comparison(
+                            Operator::Equal,
+                            Value::Constant("a".into()),
+                        ))),
+                    ),
+                ]),
+            )]),
+        );
+
+        let mut left: Vec<DataType>;
+        left = vec![1.into(), "a".into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![2.into(), "b".into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![2.into(), "a".into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+        // NOT of an unknown comparison is still unknown
+        left = vec![2.into(), DataType::None];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+        // but unknown OR true is true
+        left = vec![1.into(), DataType::None];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+
+        // x = 1 AND y = true
+        let mut g = setup(
+            false,
+            Some(&[(
+                0,
+                FilterCondition::And(vec![
+                    (
+                        0,
+                        FilterCondition::Comparison(Operator::Equal, Value::Constant(1.into())),
+                    ),
+                    (
+                        1,
+                        FilterCondition::Comparison(Operator::Equal, Value::Constant(true.into())),
+                    ),
+                ]),
+            )]),
+        );
+        left = vec![1.into(), true.into()];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+        left = vec![1.into(), false.into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+        left = vec![2.into(), true.into()];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+    }
+
+    #[test]
+    fn it_works_with_distance() {
+        let mut g = ops::test::MockGraph::new();
+        let s = g.add_base("source", &["lat", "lon", "qlat", "qlon"]);
+        g.set_op(
+            "filter",
+            &["lat", "lon", "qlat", "qlon"],
+            Filter::new(
+                s.as_global(),
+                &[(
+                    0,
+                    FilterCondition::Distance {
+                        lon: 1,
+                        from: (Value::Column(2), Value::Column(3)),
+                        op: Operator::LessOrEqual,
+                        radius: 10.0,
+                    },
+                )],
+            ),
+            false,
+        );
+
+        let mut left: Vec<DataType>;
+
+        // Cambridge, MA is within 10km of Boston
+        left = vec![
+            42.3736.into(),
+            (-71.1097).into(),
+            42.3601.into(),
+            (-71.0589).into(),
+        ];
+        assert_eq!(g.narrow_one_row(left.clone(), false), vec![left].into());
+
+        // New York is not
+        left = vec![
+            40.7128.into(),
+            (-74.0060).into(),
+            42.3601.into(),
+            (-71.0589).into(),
+        ];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+
+        // rows without a position never match
+        left = vec![
+            DataType::None,
+            DataType::None,
+            42.3601.into(),
+            (-71.0589).into(),
+        ];
+        assert!(g.narrow_one_row(left.clone(), false).is_empty());
+    }
+
+    #[test]
+    fn it_measures_distance_from_read_arguments() {
+        let near_boston = FilterCondition::Distance {
+            lon: 1,
+            from: (Value::Argument(0), Value::Argument(1)),
+            op: Operator::LessOrEqual,
+            radius: 10.0,
+        };
+        assert!(near_boston.takes_arguments());
+
+        let cambridge: Vec<DataType> = vec![42.3736.into(), (-71.1097).into()];
+        let boston: Vec<DataType> = vec![42.3601.into(), (-71.0589).into()];
+        let new_york: Vec<DataType> = vec![40.7128.into(), (-74.0060).into()];
+        assert!(near_boston.matches_with_args(&cambridge[0], &cambridge, &boston));
+        assert!(!near_boston.matches_with_args(&cambridge[0], &cambridge, &new_york));
+        // without a point to measure from, nothing is near
+        assert!(!near_boston.matches(&cambridge[0], &cambridge));
+
+        // positions that aren't numbers are never near anything
+        let nowhere: Vec<DataType> = vec!["north".into(), "west".into()];
+        assert!(!near_boston.matches_with_args(&nowhere[0], &nowhere, &boston));
+        assert!(!near_boston.matches_with_args(&cambridge[0], &cambridge, &nowhere));
+    }
+
+    #[test]
+    fn haversine_is_accurate() {
+        // Boston to New York is roughly 306km
+        let d = haversine_distance((42.3601, -71.0589), (40.7128, -74.0060));
+        assert!((d - 306.0).abs() < 2.0, "{}", d);
+        assert!(haversine_distance((1.0, 2.0), (1.0, 2.0)).abs() < 1e-9);
+    }
 }
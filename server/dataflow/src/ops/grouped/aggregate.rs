@@ -6,8 +6,13 @@ use crate::prelude::*;
 /// Supported aggregation operators.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Aggregation {
-    /// Count the number of records for each group. The value for the `over` column is ignored.
+    /// Count the number of records for each group whose `over` column is not NULL.
     COUNT,
+    /// Count the number of records for each group, including those whose `over` column is NULL.
+    ///
+    /// This is what `COUNT(*)` becomes; the `over` column is ignored.
+    #[allow(non_camel_case_types)]
+    COUNT_STAR,
     /// Sum the value of the `over` column for all records of each group.
     SUM,
 }
@@ -60,7 +65,8 @@ pub struct Aggregator {
 }
 
 impl GroupedOperation for Aggregator {
-    type Diff = i128;
+    /// The change to the aggregated value, along with its scale if it came from a decimal.
+    type Diff = (i128, Option<u8>);
 
     fn setup(&mut self, parent: &Node) {
         assert!(
@@ -75,21 +81,27 @@ impl GroupedOperation for Aggregator {
 
     fn to_diff(&self, r: &[DataType], pos: bool) -> Self::Diff {
         match self.op {
-            Aggregation::COUNT if pos => 1,
-            Aggregation::COUNT => -1,
+            // COUNT(col) only counts non-NULL values
+            Aggregation::COUNT if r[self.over] == DataType::None => (0, None),
+            Aggregation::COUNT if pos => (1, None),
+            Aggregation::COUNT => (-1, None),
+            Aggregation::COUNT_STAR if pos => (1, None),
+            Aggregation::COUNT_STAR => (-1, None),
             Aggregation::SUM => {
-                let v = match r[self.over] {
-                    DataType::Int(n) => i128::from(n),
-                    DataType::UnsignedInt(n) => i128::from(n),
-                    DataType::BigInt(n) => i128::from(n),
-                    DataType::UnsignedBigInt(n) => i128::from(n),
-                    DataType::None => 0,
+                let (v, scale) = match r[self.over] {
+                    DataType::Int(n) => (i128::from(n), None),
+                    DataType::UnsignedInt(n) => (i128::from(n), None),
+                    DataType::BigInt(n) => (i128::from(n), None),
+                    DataType::UnsignedBigInt(n) => (i128::from(n), None),
+                    // decimals are summed exactly, without going through f64
+                    DataType::Decimal(m, scale) => (i128::from(m), Some(scale)),
+                    DataType::None => (0, None),
                     ref x => unreachable!("tried to aggregate over {:?} on {:?}", x, r),
                 };
                 if pos {
-                    v
+                    (v, scale)
                 } else {
-                    0i128 - v
+                    (0i128 - v, scale)
                 }
             }
         }
@@ -101,26 +113,44 @@ impl GroupedOperation for Aggregator {
         diffs: &mut dyn Iterator<Item = Self::Diff>,
     ) -> DataType {
         let n = match current {
-            Some(&DataType::Int(n)) => i128::from(n),
-            Some(&DataType::UnsignedInt(n)) => i128::from(n),
-            Some(&DataType::BigInt(n)) => i128::from(n),
-            Some(&DataType::UnsignedBigInt(n)) => i128::from(n),
-            None => 0,
+            Some(&DataType::Int(n)) => (i128::from(n), None),
+            Some(&DataType::UnsignedInt(n)) => (i128::from(n), None),
+            Some(&DataType::BigInt(n)) => (i128::from(n), None),
+            Some(&DataType::UnsignedBigInt(n)) => (i128::from(n), None),
+            Some(&DataType::Decimal(m, scale)) => (i128::from(m), Some(scale)),
+            // the sum no longer fit in a decimal at some point, so it can't be kept up to date
+            Some(&DataType::None) => return DataType::None,
+            None => (0, None),
             _ => unreachable!(),
         };
-        diffs.fold(n, |n, d| n + d).into()
+        let (n, scale) = diffs.fold(n, |(n, ns), (d, ds)| match (ns, ds) {
+            (None, None) => (n + d, None),
+            _ => {
+                // bring both values to the larger scale
+                let (ns, ds) = (ns.unwrap_or(0), ds.unwrap_or(0));
+                let scale = std::cmp::max(ns, ds);
+                let n = n * 10i128.pow(u32::from(scale - ns));
+                let d = d * 10i128.pow(u32::from(scale - ds));
+                (n + d, Some(scale))
+            }
+        });
+        match scale {
+            // a decimal sum that doesn't fit is NULL
+            Some(scale) => DataType::decimal(n, scale).unwrap_or(DataType::None),
+            None => n.into(),
+        }
     }
 
     fn description(&self, detailed: bool) -> String {
         if !detailed {
             return String::from(match self.op {
-                Aggregation::COUNT => "+",
+                Aggregation::COUNT | Aggregation::COUNT_STAR => "+",
                 Aggregation::SUM => "𝛴",
             });
         }
 
         let op_string = match self.op {
-            Aggregation::COUNT => "|*|".into(),
+            Aggregation::COUNT | Aggregation::COUNT_STAR => "|*|".into(),
             Aggregation::SUM => format!("𝛴({})", self.over),
         };
         let group_cols = self
@@ -178,6 +208,66 @@ mod tests {
         assert_eq!(s.description(true), "𝛴(1) γ[2, 0]");
     }
 
+    #[test]
+    fn it_does_not_count_nulls() {
+        let mut c = setup(true);
+
+        // a group with only NULLs has a count of zero
+        let rs = c.narrow_one_row(vec![1.into(), DataType::None], true);
+        assert_eq!(rs, vec![(vec![1.into(), 0.into()], true)].into());
+
+        let rs = c.narrow_one_row(vec![1.into(), 1.into()], true);
+        assert_eq!(
+            rs,
+            vec![
+                (vec![1.into(), 0.into()], false),
+                (vec![1.into(), 1.into()], true),
+            ]
+            .into()
+        );
+
+        // adding or removing a NULL leaves the count alone
+        let rs = c.narrow_one_row(vec![1.into(), DataType::None], true);
+        assert!(rs.is_empty());
+        let rs = c.narrow_one_row((vec![1.into(), DataType::None], false), true);
+        assert!(rs.is_empty());
+    }
+
+    #[test]
+    fn it_counts_nulls_for_count_star() {
+        let mut c = ops::test::MockGraph::new();
+        let s = c.add_base("source", &["x", "y"]);
+        c.set_op(
+            "identity",
+            &["x", "ys"],
+            Aggregation::COUNT_STAR.over(s.as_global(), 1, &[0]),
+            true,
+        );
+
+        let rs = c.narrow_one_row(vec![1.into(), DataType::None], true);
+        assert_eq!(rs, vec![(vec![1.into(), 1.into()], true)].into());
+
+        let rs = c.narrow_one_row(vec![1.into(), 1.into()], true);
+        assert_eq!(
+            rs,
+            vec![
+                (vec![1.into(), 1.into()], false),
+                (vec![1.into(), 2.into()], true),
+            ]
+            .into()
+        );
+
+        let rs = c.narrow_one_row((vec![1.into(), DataType::None], false), true);
+        assert_eq!(
+            rs,
+            vec![
+                (vec![1.into(), 2.into()], false),
+                (vec![1.into(), 1.into()], true),
+            ]
+            .into()
+        );
+    }
+
     #[test]
     #[allow(clippy::cognitive_complexity)]
     fn it_forwards() {
@@ -412,4 +502,56 @@ mod tests {
         );
         assert_eq!(c.node().resolve(1), None);
     }
+
+    #[test]
+    fn it_sums_decimals_exactly() {
+        let mut g = ops::test::MockGraph::new();
+        let s = g.add_base("source", &["x", "y"]);
+        g.set_op(
+            "identity",
+            &["x", "ys"],
+            Aggregation::SUM.over(s.as_global(), 1, &[0]),
+            true,
+        );
+
+        let tenth = DataType::parse_decimal("0.1").unwrap();
+        for _ in 0..9 {
+            g.narrow_one_row(vec![1.into(), tenth.clone()], true);
+        }
+        let rs = g.narrow_one_row(vec![1.into(), DataType::Decimal(10, 2)], true);
+        assert!(rs.into_iter().any(|r| match r {
+            Record::Positive(r) => r[1] == 1.into() && r[1].is_decimal(),
+            _ => false,
+        }));
+
+        let rs = g.narrow_one_row((vec![1.into(), tenth], false), true);
+        assert!(rs.into_iter().any(|r| match r {
+            Record::Positive(r) => r[1] == DataType::Decimal(9, 1),
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn it_sums_overflowing_decimals_to_null() {
+        let mut g = ops::test::MockGraph::new();
+        let s = g.add_base("source", &["x", "y"]);
+        g.set_op(
+            "identity",
+            &["x", "ys"],
+            Aggregation::SUM.over(s.as_global(), 1, &[0]),
+            true,
+        );
+
+        let big = DataType::Decimal(i64::max_value(), 0);
+        g.narrow_one_row(vec![1.into(), big.clone()], true);
+        let rs = g.narrow_one_row(vec![1.into(), big.clone()], true);
+        assert!(rs.into_iter().any(|r| match r {
+            Record::Positive(r) => r[1] == DataType::None,
+            _ => false,
+        }));
+
+        // and stays that way, since the sum is no longer known
+        let rs = g.narrow_one_row((vec![1.into(), big], false), true);
+        assert!(rs.is_empty());
+    }
 }
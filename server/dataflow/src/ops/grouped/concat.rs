@@ -81,7 +81,9 @@ impl GroupConcat {
         )
     }
 
-    fn build(&self, rec: &[DataType]) -> String {
+    /// Build the string representation of the given record, or `None` if any of the columns it
+    /// uses is NULL.
+    fn build(&self, rec: &[DataType]) -> Option<String> {
         let mut s = String::with_capacity(self.slen);
         for tc in &self.components {
             match *tc {
@@ -97,19 +99,25 @@ impl GroupConcat {
                     DataType::UnsignedInt(ref n) => s.push_str(&n.to_string()),
                     DataType::BigInt(ref n) => s.push_str(&n.to_string()),
                     DataType::UnsignedBigInt(ref n) => s.push_str(&n.to_string()),
-                    DataType::Real(..) => s.push_str(&rec[*i].to_string()),
+                    DataType::Real(..)
+                    | DataType::Decimal(..)
+                    | DataType::Bytes(..)
+                    | DataType::Uuid(..)
+                    | DataType::Bool(..)
+                    | DataType::Float(..) => s.push_str(&rec[*i].to_string()),
                     DataType::Timestamp(ref ts) => s.push_str(&ts.format("%+").to_string()),
-                    DataType::None => unreachable!(),
+                    DataType::None => return None,
                 },
             }
         }
 
-        s
+        Some(s)
     }
 }
 
 impl GroupedOperation for GroupConcat {
-    type Diff = Modify;
+    /// `None` for records that are left out because they contain a NULL.
+    type Diff = Option<Modify>;
 
     fn setup(&mut self, parent: &Node) {
         // group by all columns
@@ -142,11 +150,11 @@ impl GroupedOperation for GroupConcat {
     }
 
     fn to_diff(&self, r: &[DataType], pos: bool) -> Self::Diff {
-        let v = self.build(r);
+        let v = self.build(r)?;
         if pos {
-            Modify::Add(v)
+            Some(Modify::Add(v))
         } else {
-            Modify::Remove(v)
+            Some(Modify::Remove(v))
         }
     }
 
@@ -167,7 +175,7 @@ impl GroupedOperation for GroupConcat {
         use std::borrow::Cow;
         let current: &str = match current {
             Some(dt @ &DataType::Text(..)) | Some(dt @ &DataType::TinyText(..)) => dt.into(),
-            None => "",
+            Some(&DataType::None) | None => "",
             _ => unreachable!(),
         };
         let clen = current.len();
@@ -178,7 +186,7 @@ impl GroupedOperation for GroupConcat {
                 .split_terminator(&self.separator)
                 .map(|s| Cow::Borrowed(s)),
         );
-        for diff in diffs {
+        for diff in diffs.flatten() {
             match diff {
                 Modify::Add(s) => {
                     current.insert(Cow::Owned(s));
@@ -189,6 +197,11 @@ impl GroupedOperation for GroupConcat {
             }
         }
 
+        // like in SQL, a group with nothing but NULLs concatenates to NULL
+        if current.is_empty() {
+            return DataType::None;
+        }
+
         // WHY doesn't rust have an iterator joiner?
         let mut new = current
             .into_iter()
@@ -425,6 +438,29 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn it_skips_nulls() {
+        let mut c = setup(true);
+
+        // a group with only NULLs concatenates to NULL
+        let rs = c.narrow_one_row(vec![1.into(), DataType::None], true);
+        assert_eq!(rs, vec![(vec![1.into(), DataType::None], true)].into());
+
+        let rs = c.narrow_one_row(vec![1.into(), 2.into()], true);
+        assert_eq!(
+            rs,
+            vec![
+                (vec![1.into(), DataType::None], false),
+                (vec![1.into(), ".2;".into()], true),
+            ]
+            .into()
+        );
+
+        // NULLs don't show up in the concatenation
+        let rs = c.narrow_one_row(vec![1.into(), DataType::None], true);
+        assert!(rs.is_empty());
+    }
+
     #[test]
     fn it_suggests_indices() {
         let me = 1.into();
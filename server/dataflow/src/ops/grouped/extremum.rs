@@ -61,6 +61,8 @@ pub struct ExtremumOperator {
 pub enum DiffType {
     Insert(i128),
     Remove(i128),
+    /// A NULL value was inserted or removed; NULLs never contribute to an extremum.
+    Null,
 }
 
 impl GroupedOperation for ExtremumOperator {
@@ -83,6 +85,7 @@ impl GroupedOperation for ExtremumOperator {
             DataType::UnsignedInt(n) => i128::from(n),
             DataType::BigInt(n) => i128::from(n),
             DataType::UnsignedBigInt(n) => i128::from(n),
+            DataType::None => return DiffType::Null,
             _ => {
                 // the column we're aggregating over is non-numerical (or rather, this value is).
                 // if you've removed a column, chances are the  default value has the wrong type.
@@ -111,6 +114,8 @@ impl GroupedOperation for ExtremumOperator {
     ) -> DataType {
         // Extreme values are those that are at least as extreme as the current min/max (if any).
         // let mut is_extreme_value : Box<dyn Fn(i64) -> bool> = Box::new(|_|true);
+        // A NULL output means that the group has so far only seen NULLs.
+        let current = current.filter(|data| !data.is_none());
         let mut extreme_values: Vec<i128> = vec![];
         if let Some(data) = current {
             match *data {
@@ -140,7 +145,12 @@ impl GroupedOperation for ExtremumOperator {
             }
         };
 
+        let mut only_nulls = true;
         for d in diffs {
+            if let DiffType::Null = d {
+                continue;
+            }
+            only_nulls = false;
             match d {
                 DiffType::Insert(v) if is_extreme_value(v) => extreme_values.push(v),
                 DiffType::Remove(v) if is_extreme_value(v) => {
@@ -161,6 +171,10 @@ impl GroupedOperation for ExtremumOperator {
             return extreme.into();
         }
 
+        if current.is_none() && only_nulls {
+            return DataType::None;
+        }
+
         // TODO: handle this case by querying into the parent.
         unimplemented!();
     }
@@ -309,6 +323,33 @@ mod tests {
         assert_record_change(key, 7, 5, out);
     }
 
+    #[test]
+    fn it_ignores_nulls() {
+        let mut c = setup(Extremum::MAX, true);
+        let key = 1;
+
+        // A group with only NULLs has a NULL extremum.
+        let out = c.narrow_one_row(vec![key.into(), DataType::None], true);
+        assert_eq!(out, vec![vec![key.into(), DataType::None]].into());
+
+        // The first non-NULL value replaces it.
+        let out = c.narrow_one_row(vec![key.into(), 4.into()], true);
+        assert_eq!(
+            out,
+            vec![
+                (vec![key.into(), DataType::None], false),
+                (vec![key.into(), 4.into()], true),
+            ]
+            .into()
+        );
+
+        // Further NULLs (in either direction) do not affect the maximum.
+        let rs = c.narrow_one_row(vec![key.into(), DataType::None], true);
+        assert!(rs.is_empty());
+        let rs = c.narrow_one_row((vec![key.into(), DataType::None], false), true);
+        assert!(rs.is_empty());
+    }
+
     #[test]
     fn it_cancels_out_opposite_records() {
         let mut c = setup(Extremum::MAX, true);
@@ -43,6 +43,12 @@ pub struct Join {
     in_place_right_emit: Vec<(bool, usize)>,
 
     kind: JoinType,
+
+    // How many times we looked up a join key in the other side, and how many of those missed
+    #[serde(skip)]
+    probes: u64,
+    #[serde(skip)]
+    probe_misses: u64,
 }
 
 enum Preprocessed {
@@ -121,6 +127,8 @@ impl Join {
             in_place_left_emit,
             in_place_right_emit,
             kind,
+            probes: 0,
+            probe_misses: 0,
         }
     }
 
@@ -207,6 +215,13 @@ impl Ingredient for Join {
         true
     }
 
+    fn probe(&self) -> HashMap<String, String> {
+        let mut hm = HashMap::new();
+        hm.insert("probes".into(), format!("{}", self.probes));
+        hm.insert("misses".into(), format!("{}", self.probe_misses));
+        hm
+    }
+
     fn must_replay_among(&self) -> Option<HashSet<NodeIndex>> {
         match self.kind {
             JoinType::Left => Some(Some(self.left.as_global()).into_iter().collect()),
@@ -292,6 +307,23 @@ impl Ingredient for Join {
             let mut new_right_count = None;
             let prev_join_key = rs[at][from_key].clone();
 
+            if prev_join_key.is_none() {
+                // NULL is not equal to anything (not even NULL), so these rows never match. a
+                // left join still has to produce the left rows (with NULLs for the right side).
+                let start = at;
+                at = rs[at..]
+                    .iter()
+                    .position(|r| !r[from_key].is_none())
+                    .map(|p| at + p)
+                    .unwrap_or_else(|| rs.len());
+                if self.kind == JoinType::Left && from == *self.left {
+                    for r in &rs[start..at] {
+                        ret.push((self.generate_null(r), r.is_positive()).into());
+                    }
+                }
+                continue;
+            }
+
             if from == *self.right && self.kind == JoinType::Left {
                 let rc = self
                     .lookup(
@@ -335,6 +367,7 @@ impl Ingredient for Join {
             }
 
             // get rows from the other side
+            self.probes += 1;
             let mut other_rows = self
                 .lookup(
                     other,
@@ -347,6 +380,7 @@ impl Ingredient for Join {
 
             if other_rows.is_none() {
                 // we missed in the other side!
+                self.probe_misses += 1;
                 let from = at;
                 at = rs[at..]
                     .iter()
@@ -737,6 +771,31 @@ mod tests {
         assert_eq!(rs.len(), 0);
     }
 
+    #[test]
+    fn it_never_matches_null_keys() {
+        let (mut j, l, r) = setup();
+        let l_null = vec![DataType::None, "a".into()];
+        let r_null = vec![DataType::None, "x".into()];
+
+        j.seed(r, r_null.clone());
+        let rs = j.one_row(r, r_null.clone(), false);
+        assert!(rs.is_empty());
+
+        // left row with a NULL key should not join with the NULL-keyed right row
+        j.seed(l, l_null.clone());
+        let rs = j.one_row(l, l_null.clone(), false);
+        assert_eq!(
+            rs,
+            vec![(vec![DataType::None, "a".into(), DataType::None], true)].into()
+        );
+
+        // and more NULL-keyed rows from the right should not revoke it
+        let r_null2 = vec![DataType::None, "y".into()];
+        j.seed(r, r_null2.clone());
+        let rs = j.one_row(r, r_null2.clone(), false);
+        assert!(rs.is_empty());
+    }
+
     #[test]
     fn it_suggests_indices() {
         use std::collections::HashMap;
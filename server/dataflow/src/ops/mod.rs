@@ -1,6 +1,7 @@
 use slog::Logger;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
 
 use crate::prelude::*;
 
@@ -33,6 +34,8 @@ pub enum NodeOperator {
     Trigger(trigger::Trigger),
     Rewrite(rewrite::Rewrite),
     Distinct(distinct::Distinct),
+    #[cfg(test)]
+    RangeReader(test::RangeReader),
 }
 
 macro_rules! nodeop_from_impl {
@@ -71,6 +74,8 @@ nodeop_from_impl!(NodeOperator::TopK, topk::TopK);
 nodeop_from_impl!(NodeOperator::Trigger, trigger::Trigger);
 nodeop_from_impl!(NodeOperator::Rewrite, rewrite::Rewrite);
 nodeop_from_impl!(NodeOperator::Distinct, distinct::Distinct);
+#[cfg(test)]
+nodeop_from_impl!(NodeOperator::RangeReader, test::RangeReader);
 
 macro_rules! impl_ingredient_fn_mut {
     ($self:ident, $fn:ident, $( $arg:ident ),* ) => {
@@ -89,6 +94,8 @@ macro_rules! impl_ingredient_fn_mut {
             NodeOperator::Trigger(ref mut i) => i.$fn($($arg),*),
             NodeOperator::Rewrite(ref mut i) => i.$fn($($arg),*),
             NodeOperator::Distinct(ref mut i) => i.$fn($($arg),*),
+            #[cfg(test)]
+            NodeOperator::RangeReader(ref mut i) => i.$fn($($arg),*),
         }
     }
 }
@@ -110,6 +117,8 @@ macro_rules! impl_ingredient_fn_ref {
             NodeOperator::Trigger(ref i) => i.$fn($($arg),*),
             NodeOperator::Rewrite(ref i) => i.$fn($($arg),*),
             NodeOperator::Distinct(ref i) => i.$fn($($arg),*),
+            #[cfg(test)]
+            NodeOperator::RangeReader(ref i) => i.$fn($($arg),*),
         }
     }
 }
@@ -127,6 +136,9 @@ impl Ingredient for NodeOperator {
     fn suggest_indexes(&self, you: NodeIndex) -> HashMap<NodeIndex, Vec<usize>> {
         impl_ingredient_fn_ref!(self, suggest_indexes, you)
     }
+    fn suggest_ordered_indexes(&self, you: NodeIndex) -> HashMap<NodeIndex, Vec<usize>> {
+        impl_ingredient_fn_ref!(self, suggest_ordered_indexes, you)
+    }
     fn resolve(&self, i: usize) -> Option<Vec<(NodeIndex, usize)>> {
         impl_ingredient_fn_ref!(self, resolve, i)
     }
@@ -214,6 +226,15 @@ impl Ingredient for NodeOperator {
     ) -> Option<Option<Box<dyn Iterator<Item = Cow<'a, [DataType]>> + 'a>>> {
         impl_ingredient_fn_ref!(self, lookup, parent, columns, key, domain, states)
     }
+    fn lookup_range<'a>(
+        &self,
+        parent: LocalNodeIndex,
+        column: usize,
+        range: (Bound<DataType>, Bound<DataType>),
+        states: &'a StateMap,
+    ) -> Option<Box<dyn Iterator<Item = Cow<'a, [DataType]>> + 'a>> {
+        impl_ingredient_fn_ref!(self, lookup_range, parent, column, range, states)
+    }
     fn parent_columns(&self, column: usize) -> Vec<(NodeIndex, Option<usize>)> {
         impl_ingredient_fn_ref!(self, parent_columns, column)
     }
@@ -229,6 +250,7 @@ impl Ingredient for NodeOperator {
 pub mod test {
     use std::cell;
     use std::collections::HashMap;
+    use std::ops::Bound;
 
     use crate::node;
     use crate::prelude::*;
@@ -333,6 +355,15 @@ pub mod test {
                     s.add_key(&col[..], None);
                 }
             }
+            let ordered = self.graph[global].suggest_ordered_indexes(global);
+            for (tbl, cols) in ordered {
+                if let Some(ref mut s) = self.states.get_mut(self.graph[tbl].local_addr()) {
+                    for col in cols {
+                        s.add_key(&[col], None);
+                        s.add_ordered_key(col);
+                    }
+                }
+            }
             // and get rid of states we don't need
             let unused: Vec<_> = self
                 .remap
@@ -408,6 +439,15 @@ pub mod test {
                     state.add_key(&col[..], None);
                 }
             }
+            let ordered = self.graph[global].suggest_ordered_indexes(global);
+            for (tbl, cols) in ordered {
+                if tbl == base.as_global() {
+                    for col in cols {
+                        state.add_key(&[col], None);
+                        state.add_ordered_key(col);
+                    }
+                }
+            }
 
             self.states.insert(*base, Box::new(state));
         }
@@ -419,9 +459,12 @@ pub mod test {
             struct Ex;
 
             impl Executor for Ex {
-                fn ack(&mut self, _: SourceChannelIdentifier) {}
+                fn ack(&mut self, _: SourceChannelIdentifier, _: WriteReply) {}
                 fn create_universe(&mut self, _: HashMap<String, DataType>) {}
                 fn send(&mut self, _: ReplicaAddr, _: Box<Packet>) {}
+                fn queued(&self) -> usize {
+                    0
+                }
             }
 
             let mut u = {
@@ -458,6 +501,67 @@ pub mod test {
             self.narrow_one::<Record>(d.into(), remember)
         }
 
+        /// Feed `rounds` random batches of positives and negatives through the (narrow) node
+        /// under test, and check after every batch that the accumulated output equals
+        /// `semantics` applied to the accumulated input.
+        ///
+        /// `gen` produces new input rows. Negatives are always drawn from rows that are currently
+        /// live in the input, so the input multiset never goes negative. `semantics` is handed
+        /// the live input rows and should return the rows the operator is expected to have
+        /// produced from them; order is irrelevant. The seed is included in any failure message
+        /// so that a failing sequence can be reproduced.
+        pub fn check_deltas<G, S>(&mut self, seed: u64, rounds: usize, mut gen: G, semantics: S)
+        where
+            G: FnMut(&mut rand::rngs::StdRng) -> Vec<DataType>,
+            S: Fn(&[Vec<DataType>]) -> Vec<Vec<DataType>>,
+        {
+            use rand::{Rng, SeedableRng};
+
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            let remember = self.states.contains_key(*self.nut.unwrap());
+            let mut input: Vec<Vec<DataType>> = Vec::new();
+            let mut output: Vec<Vec<DataType>> = Vec::new();
+
+            for round in 0..rounds {
+                let mut batch = Vec::new();
+                for _ in 0..rng.gen_range(1, 8) {
+                    if !input.is_empty() && rng.gen_bool(0.3) {
+                        let r = input.swap_remove(rng.gen_range(0, input.len()));
+                        batch.push(Record::Negative(r));
+                    } else {
+                        let r = gen(&mut rng);
+                        input.push(r.clone());
+                        batch.push(Record::Positive(r));
+                    }
+                }
+
+                for r in self.narrow_one(batch, remember) {
+                    let (r, positive) = r.extract();
+                    if positive {
+                        output.push(r);
+                    } else {
+                        let i = output.iter().position(|o| *o == r).unwrap_or_else(|| {
+                            panic!(
+                                "seed {}, round {}: negative for row never emitted: {:?}",
+                                seed, round, r
+                            )
+                        });
+                        output.swap_remove(i);
+                    }
+                }
+
+                let mut expected = semantics(&input[..]);
+                expected.sort();
+                let mut actual = output.clone();
+                actual.sort();
+                assert_eq!(
+                    actual, expected,
+                    "seed {}, round {}: accumulated output diverged from semantics",
+                    seed, round
+                );
+            }
+        }
+
         pub fn node(&self) -> cell::Ref<Node> {
             self.nodes[*self.nut.unwrap()].borrow()
         }
@@ -472,4 +576,111 @@ pub mod test {
                 .unwrap()
         }
     }
+
+    /// An operator that, for every row it receives, emits the rows of its parent whose `column`
+    /// is strictly smaller, in order. It exists to exercise ordered indices, which no other
+    /// operator uses yet.
+    #[derive(Clone, Serialize, Deserialize)]
+    pub struct RangeReader {
+        src: IndexPair,
+        column: usize,
+    }
+
+    impl RangeReader {
+        pub fn new(src: NodeIndex, column: usize) -> Self {
+            RangeReader {
+                src: src.into(),
+                column,
+            }
+        }
+    }
+
+    impl Ingredient for RangeReader {
+        fn take(&mut self) -> NodeOperator {
+            Clone::clone(self).into()
+        }
+
+        fn ancestors(&self) -> Vec<NodeIndex> {
+            vec![self.src.as_global()]
+        }
+
+        fn on_connected(&mut self, _: &Graph) {}
+
+        fn on_commit(&mut self, _: NodeIndex, remap: &HashMap<NodeIndex, IndexPair>) {
+            self.src.remap(remap);
+        }
+
+        fn on_input(
+            &mut self,
+            _: &mut dyn Executor,
+            _: LocalNodeIndex,
+            rs: Records,
+            _: Option<&[usize]>,
+            _: &DomainNodes,
+            states: &StateMap,
+        ) -> ProcessingResult {
+            let mut results = Vec::new();
+            for r in rs.iter() {
+                let range = (Bound::Unbounded, Bound::Excluded(r[self.column].clone()));
+                let rows = self
+                    .lookup_range(*self.src, self.column, range, states)
+                    .expect("parent of range reader must be materialized");
+                results.extend(rows.map(|r| Record::Positive(r.into_owned())));
+            }
+            ProcessingResult {
+                results: results.into(),
+                ..Default::default()
+            }
+        }
+
+        fn suggest_indexes(&self, _: NodeIndex) -> HashMap<NodeIndex, Vec<usize>> {
+            HashMap::new()
+        }
+
+        fn suggest_ordered_indexes(&self, _: NodeIndex) -> HashMap<NodeIndex, Vec<usize>> {
+            vec![(self.src.as_global(), vec![self.column])]
+                .into_iter()
+                .collect()
+        }
+
+        fn resolve(&self, _: usize) -> Option<Vec<(NodeIndex, usize)>> {
+            None
+        }
+
+        fn description(&self, _: bool) -> String {
+            format!("<f{}", self.column)
+        }
+
+        fn parent_columns(&self, column: usize) -> Vec<(NodeIndex, Option<usize>)> {
+            vec![(self.src.as_global(), Some(column))]
+        }
+    }
+
+    #[test]
+    fn it_serves_range_lookups_from_declared_ordered_indices() {
+        let mut g = MockGraph::new();
+        let s = g.add_base("source", &["x", "y"]);
+        g.set_op(
+            "range",
+            &["x", "y"],
+            RangeReader::new(s.as_global(), 1),
+            false,
+        );
+        for (x, y) in &[(1, 30), (2, 10), (3, 40), (4, 20)] {
+            g.seed(s, vec![(*x).into(), (*y).into()]);
+        }
+
+        let rs: Vec<_> = g.narrow_one_row(vec![0.into(), 35.into()], false).into();
+        assert_eq!(
+            rs,
+            vec![
+                Record::Positive(vec![2.into(), 10.into()]),
+                Record::Positive(vec![4.into(), 20.into()]),
+                Record::Positive(vec![1.into(), 30.into()]),
+            ]
+        );
+
+        let rs: Vec<_> = g.narrow_one_row(vec![0.into(), 10.into()], false).into();
+        assert!(rs.is_empty());
+    }
 }
@@ -412,6 +412,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_is_delta_correct() {
+        use rand::Rng;
+
+        let mut p = setup(false, false, true);
+        p.check_deltas(
+            0x9e7,
+            200,
+            |rng| (0..3).map(|_| rng.gen_range(0i32, 4).into()).collect(),
+            |rows| {
+                rows.iter()
+                    .map(|r| vec![r[2].clone(), r[0].clone(), "hello".into(), 42.into()])
+                    .collect()
+            },
+        );
+    }
+
     #[test]
     fn it_forwards_all_w_literals() {
         let mut p = setup(false, true, true);
@@ -44,6 +44,8 @@ pub enum TriggerEndpoint {
 pub enum InitialState {
     PartialLocal(Vec<(Vec<usize>, Vec<Tag>)>),
     IndexedLocal(HashSet<Vec<usize>>),
+    /// Add ordered indices on the given columns to a node's existing full state.
+    OrderedLocal(Vec<usize>),
     PartialGlobal {
         gid: petgraph::graph::NodeIndex,
         cols: usize,
@@ -85,13 +87,17 @@ pub enum Packet {
     Input {
         inner: LocalOrNot<Input>,
         src: Option<SourceChannelIdentifier>,
-        senders: Vec<SourceChannelIdentifier>,
+        /// The packets that were merged into this one, each with its number of operations, in
+        /// the order their operations appear. Only packets with a source need to be acked.
+        senders: Vec<(Option<SourceChannelIdentifier>, usize)>,
     },
 
     /// Regular data-flow update.
     Message {
         link: Link,
         data: Records,
+        /// The trace id of the write that caused this update, if any.
+        trace: Option<u64>,
     },
 
     /// Update that is part of a tagged data-flow replay path.
@@ -224,6 +230,12 @@ pub enum Packet {
     /// Argument specifies if we wish to get the full state size or just the partial nodes.
     GetStatistics,
 
+    /// Request that a domain send the events it has recorded for the given trace id on the
+    /// control reply channel.
+    GetTrace {
+        trace: u64,
+    },
+
     /// Ask domain to log its state size
     UpdateStateSize,
 }
@@ -267,6 +279,30 @@ impl Packet {
         }
     }
 
+    /// The number of records (or table operations, for inputs) carried by this packet.
+    pub(crate) fn len(&self) -> usize {
+        match *self {
+            Packet::Input { ref inner, .. } => unsafe { inner.deref() }.data.len(),
+            Packet::Message { ref data, .. } => data.len(),
+            Packet::ReplayPiece { ref data, .. } => data.len(),
+            _ => 0,
+        }
+    }
+
+    /// A copy of the first record (or table operation, for inputs) carried by this packet.
+    pub(crate) fn sample(&self) -> Option<String> {
+        match *self {
+            Packet::Input { ref inner, .. } => unsafe { inner.deref() }
+                .data
+                .first()
+                .map(|op| format!("{:?}", op)),
+            Packet::Message { ref data, .. } | Packet::ReplayPiece { ref data, .. } => {
+                data.iter().next().map(|r| format!("{:?}", r))
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn map_data<F>(&mut self, map: F)
     where
         F: FnOnce(&mut Records),
@@ -296,6 +332,21 @@ impl Packet {
         }
     }
 
+    pub(crate) fn trace(&self) -> Option<u64> {
+        match *self {
+            Packet::Input { ref inner, .. } => unsafe { inner.deref() }.trace,
+            Packet::Message { trace, .. } => trace,
+            _ => None,
+        }
+    }
+
+    pub(crate) fn data(&self) -> &Records {
+        match *self {
+            Packet::Message { ref data, .. } | Packet::ReplayPiece { ref data, .. } => data,
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn take_data(&mut self) -> Records {
         use std::mem;
         let inner = match *self {
@@ -308,9 +359,14 @@ impl Packet {
 
     pub(crate) fn clone_data(&self) -> Self {
         match *self {
-            Packet::Message { link, ref data } => Packet::Message {
+            Packet::Message {
+                link,
+                ref data,
+                trace,
+            } => Packet::Message {
                 link,
                 data: data.clone(),
+                trace,
             },
             Packet::ReplayPiece {
                 link,
@@ -369,6 +425,7 @@ pub enum ControlReplyPacket {
         HashMap<petgraph::graph::NodeIndex, noria::debug::stats::NodeStats>,
     ),
     Booted(usize, SocketAddr),
+    Trace(Vec<noria::debug::trace::TraceEvent>),
 }
 
 impl ControlReplyPacket {
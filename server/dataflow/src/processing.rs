@@ -1,6 +1,7 @@
 use slog::Logger;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
 
 use crate::ops;
 use crate::prelude::*;
@@ -126,6 +127,13 @@ where
     /// *compound* key, *not* that multiple columns should be independently indexed.
     fn suggest_indexes(&self, you: NodeIndex) -> HashMap<NodeIndex, Vec<usize>>;
 
+    /// Columns of this view, or its ancestors, that this view will look up by range using
+    /// `lookup_range`. Each column is given its own ordered index, and any view with an ordered
+    /// index is fully materialized.
+    fn suggest_ordered_indexes(&self, _you: NodeIndex) -> HashMap<NodeIndex, Vec<usize>> {
+        HashMap::new()
+    }
+
     /// Resolve where the given field originates from. If the view is materialized, or the value is
     /// otherwise created by this view, None should be returned.
     fn resolve(&self, i: usize) -> Option<Vec<(NodeIndex, usize)>>;
@@ -264,6 +272,21 @@ where
             })
     }
 
+    /// Look up all rows of the given parent whose `column` falls within `range`, in order. Returns
+    /// `None` if the parent is not materialized. The parent must have been given an ordered index
+    /// on `column` through `suggest_ordered_indexes`.
+    fn lookup_range<'a>(
+        &self,
+        parent: LocalNodeIndex,
+        column: usize,
+        range: (Bound<DataType>, Bound<DataType>),
+        states: &'a StateMap,
+    ) -> Option<Box<dyn Iterator<Item = Cow<'a, [DataType]>> + 'a>> {
+        states
+            .get(parent)
+            .map(move |state| Box::new(state.lookup_range(column, range).into_iter()) as Box<_>)
+    }
+
     /// Translate a column in this ingredient into the corresponding column(s) in
     /// parent ingredients. None for the column means that the parent doesn't
     /// have an associated column. Similar to resolve, but does not depend on
@@ -1,5 +1,6 @@
 use ahash::RandomState;
 use indexmap::IndexMap;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
 use super::mk_key::MakeKey;
@@ -16,6 +17,9 @@ pub(super) enum KeyedState {
     Quad(HashMap<(DataType, DataType, DataType, DataType), Rows>),
     Quin(HashMap<(DataType, DataType, DataType, DataType, DataType), Rows>),
     Sex(HashMap<(DataType, DataType, DataType, DataType, DataType, DataType), Rows>),
+    Multi(HashMap<Vec<DataType>, Rows>),
+    /// An index on a single column that keeps its keys sorted, so it can serve range lookups.
+    Ordered(BTreeMap<DataType, Rows>),
 }
 
 impl KeyedState {
@@ -27,55 +31,61 @@ impl KeyedState {
             (&KeyedState::Quad(ref m), &KeyType::Quad(ref k)) => m.get(k),
             (&KeyedState::Quin(ref m), &KeyType::Quin(ref k)) => m.get(k),
             (&KeyedState::Sex(ref m), &KeyType::Sex(ref k)) => m.get(k),
+            (&KeyedState::Multi(ref m), &KeyType::Multi(ref k)) => m.get(k),
+            (&KeyedState::Ordered(ref m), &KeyType::Single(k)) => m.get(k),
             _ => unreachable!(),
         }
     }
 
-    /// Remove all rows for a randomly chosen key seeded by `seed`, returning that key along with
-    /// the number of bytes freed. Returns `None` if map is empty.
-    pub(super) fn evict_with_seed(&mut self, seed: usize) -> Option<(u64, Vec<DataType>)> {
-        let (rs, key) = match *self {
-            KeyedState::Single(ref mut m) if !m.is_empty() => {
-                let index = seed % m.len();
-                m.swap_remove_index(index).map(|(k, rs)| (rs, vec![k]))
-            }
-            KeyedState::Double(ref mut m) if !m.is_empty() => {
-                let index = seed % m.len();
-                m.swap_remove_index(index)
-                    .map(|(k, rs)| (rs, vec![k.0, k.1]))
-            }
-            KeyedState::Tri(ref mut m) if !m.is_empty() => {
-                let index = seed % m.len();
-                m.swap_remove_index(index)
-                    .map(|(k, rs)| (rs, vec![k.0, k.1, k.2]))
-            }
-            KeyedState::Quad(ref mut m) if !m.is_empty() => {
-                let index = seed % m.len();
-                m.swap_remove_index(index)
-                    .map(|(k, rs)| (rs, vec![k.0, k.1, k.2, k.3]))
-            }
-            KeyedState::Quin(ref mut m) if !m.is_empty() => {
-                let index = seed % m.len();
-                m.swap_remove_index(index)
-                    .map(|(k, rs)| (rs, vec![k.0, k.1, k.2, k.3, k.4]))
-            }
-            KeyedState::Sex(ref mut m) if !m.is_empty() => {
-                let index = seed % m.len();
-                m.swap_remove_index(index)
-                    .map(|(k, rs)| (rs, vec![k.0, k.1, k.2, k.3, k.4, k.5]))
-            }
-            _ => {
-                // map must be empty, so no point in trying to evict from it.
-                return None;
-            }
-        }?;
-        Some((
-            rs.iter()
-                .filter(|r| Rc::strong_count(&r.0) == 1)
-                .map(SizeOf::deep_size_of)
-                .sum(),
-            key,
-        ))
+    /// Returns the number of keys in the map.
+    pub(super) fn len(&self) -> usize {
+        match *self {
+            KeyedState::Single(ref m) => m.len(),
+            KeyedState::Double(ref m) => m.len(),
+            KeyedState::Tri(ref m) => m.len(),
+            KeyedState::Quad(ref m) => m.len(),
+            KeyedState::Quin(ref m) => m.len(),
+            KeyedState::Sex(ref m) => m.len(),
+            KeyedState::Multi(ref m) => m.len(),
+            KeyedState::Ordered(ref m) => m.len(),
+        }
+    }
+
+    /// Returns the key stored at position `index` in the map, if any.
+    pub(super) fn key_at(&self, index: usize) -> Option<Vec<DataType>> {
+        match *self {
+            KeyedState::Single(ref m) => m.get_index(index).map(|(k, _)| vec![k.clone()]),
+            KeyedState::Double(ref m) => m
+                .get_index(index)
+                .map(|(k, _)| vec![k.0.clone(), k.1.clone()]),
+            KeyedState::Tri(ref m) => m
+                .get_index(index)
+                .map(|(k, _)| vec![k.0.clone(), k.1.clone(), k.2.clone()]),
+            KeyedState::Quad(ref m) => m
+                .get_index(index)
+                .map(|(k, _)| vec![k.0.clone(), k.1.clone(), k.2.clone(), k.3.clone()]),
+            KeyedState::Quin(ref m) => m.get_index(index).map(|(k, _)| {
+                vec![
+                    k.0.clone(),
+                    k.1.clone(),
+                    k.2.clone(),
+                    k.3.clone(),
+                    k.4.clone(),
+                ]
+            }),
+            KeyedState::Sex(ref m) => m.get_index(index).map(|(k, _)| {
+                vec![
+                    k.0.clone(),
+                    k.1.clone(),
+                    k.2.clone(),
+                    k.3.clone(),
+                    k.4.clone(),
+                    k.5.clone(),
+                ]
+            }),
+            KeyedState::Multi(ref m) => m.get_index(index).map(|(k, _)| k.clone()),
+            KeyedState::Ordered(ref m) => m.keys().nth(index).map(|k| vec![k.clone()]),
+        }
     }
 
     /// Remove all rows for the given key, returning the number of bytes freed.
@@ -97,6 +107,8 @@ impl KeyedState {
             KeyedState::Sex(ref mut m) => {
                 m.swap_remove::<(DataType, _, _, _, _, _)>(&MakeKey::from_key(key))
             }
+            KeyedState::Multi(ref mut m) => m.swap_remove(key),
+            KeyedState::Ordered(ref mut m) => m.remove(&key[0]),
         }
         .map(|rows| {
             rows.iter()
@@ -118,7 +130,7 @@ impl<'a> Into<KeyedState> for &'a [usize] {
             4 => KeyedState::Quad(HashMap::default()),
             5 => KeyedState::Quin(HashMap::default()),
             6 => KeyedState::Sex(HashMap::default()),
-            x => panic!("invalid compound key of length: {}", x),
+            _ => KeyedState::Multi(HashMap::default()),
         }
     }
 }
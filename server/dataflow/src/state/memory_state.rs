@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::ops::Bound;
 use std::rc::Rc;
 
 use rand::{self, Rng};
@@ -22,7 +23,7 @@ impl SizeOf for MemoryState {
     }
 
     fn deep_size_of(&self) -> u64 {
-        self.mem_size
+        self.mem_size + self.state.iter().map(SingleState::index_size).sum::<u64>()
     }
 
     fn is_empty(&self) -> bool {
@@ -139,6 +140,39 @@ impl State for MemoryState {
         self.state[index].lookup(key)
     }
 
+    fn add_ordered_key(&mut self, column: usize) {
+        if self.ordered_state_for(column).is_some() {
+            return;
+        }
+
+        let mut new = SingleState::new_ordered(column);
+        if let Some(old) = self.state.first() {
+            assert!(!old.partial(), "ordered index on partial state");
+            for rs in old.values() {
+                for r in rs {
+                    new.insert_row(Row::from(r.0.clone()));
+                }
+            }
+        }
+        self.state.push(new);
+    }
+
+    fn lookup_range<'a>(
+        &'a self,
+        column: usize,
+        range: (Bound<DataType>, Bound<DataType>),
+    ) -> RecordResult<'a> {
+        let index = self
+            .ordered_state_for(column)
+            .expect("range lookup on column without ordered index");
+        RecordResult::Owned(
+            self.state[index]
+                .lookup_range(range)
+                .flat_map(|rs| rs.iter().map(|r| Vec::clone(&**r)))
+                .collect(),
+        )
+    }
+
     fn keys(&self) -> Vec<Vec<usize>> {
         self.state.iter().map(|s| s.key().to_vec()).collect()
     }
@@ -153,10 +187,10 @@ impl State for MemoryState {
         self.state[0].values().flat_map(fix).collect()
     }
 
-    fn evict_random_keys(&mut self, count: usize) -> (&[usize], Vec<Vec<DataType>>, u64) {
+    fn evict_least_recent_keys(&mut self, count: usize) -> (&[usize], Vec<Vec<DataType>>, u64) {
         let mut rng = rand::thread_rng();
         let index = rng.gen_range(0, self.state.len());
-        let (bytes_freed, keys) = self.state[index].evict_random_keys(count, &mut rng);
+        let (bytes_freed, keys) = self.state[index].evict_least_recent_keys(count, &mut rng);
         self.mem_size = self.mem_size.saturating_sub(bytes_freed);
         (self.state[index].key(), keys, bytes_freed)
     }
@@ -184,7 +218,16 @@ impl MemoryState {
     /// Returns the index in `self.state` of the index keyed on `cols`, or None if no such index
     /// exists.
     fn state_for(&self, cols: &[usize]) -> Option<usize> {
-        self.state.iter().position(|s| s.key() == cols)
+        self.state
+            .iter()
+            .position(|s| s.key() == cols && !s.is_ordered())
+    }
+
+    /// Returns the index in `self.state` of the ordered index on `column`, if there is one.
+    fn ordered_state_for(&self, column: usize) -> Option<usize> {
+        self.state
+            .iter()
+            .position(|s| s.key() == [column] && s.is_ordered())
     }
 
     fn insert(&mut self, r: Vec<DataType>, partial_tag: Option<Tag>) -> bool {
@@ -269,6 +312,110 @@ mod tests {
         }
     }
 
+    #[test]
+    fn memory_state_wide_compound_key() {
+        let mut state = MemoryState::default();
+        let cols = [0, 1, 2, 3, 4, 5, 6];
+        let row: Vec<DataType> = (0..8).map(DataType::from).collect();
+        state.add_key(&cols, None);
+        insert(&mut state, row.clone());
+
+        let key: Vec<DataType> = (0..7).map(DataType::from).collect();
+        match state.lookup(&cols, &KeyType::from(&key[..])) {
+            LookupResult::Some(RecordResult::Borrowed(rows)) => {
+                assert_eq!(rows.len(), 1);
+                assert_eq!(&**rows.iter().next().unwrap(), &row);
+            }
+            _ => unreachable!(),
+        }
+
+        state.process_records(&mut vec![(row, false)].into(), None);
+        match state.lookup(&cols, &KeyType::from(&key[..])) {
+            LookupResult::Some(RecordResult::Borrowed(rows)) => assert_eq!(rows.len(), 0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn memory_state_evicts_least_recently_read() {
+        let mut state = MemoryState::default();
+        let tag = Tag::new(0);
+        state.add_key(&[0], Some(vec![tag]));
+        for k in 0..3 {
+            state.mark_filled(vec![k.into()], tag);
+            insert(&mut state, vec![k.into(), k.into()]);
+        }
+
+        // key 0 was filled first, but read since key 1 was filled
+        let key = DataType::from(0);
+        match state.lookup(&[0], &KeyType::Single(&key)) {
+            LookupResult::Some(RecordResult::Borrowed(rows)) => assert_eq!(rows.len(), 1),
+            _ => unreachable!(),
+        }
+
+        let (_, evicted, bytes) = state.evict_least_recent_keys(1);
+        assert_eq!(evicted, vec![vec![1.into()]]);
+        assert!(bytes > 0);
+        let key = DataType::from(1);
+        match state.lookup(&[0], &KeyType::Single(&key)) {
+            LookupResult::Missing => {}
+            _ => unreachable!(),
+        }
+
+        let (_, evicted, _) = state.evict_least_recent_keys(1);
+        assert_eq!(evicted, vec![vec![2.into()]]);
+    }
+
+    #[test]
+    fn memory_state_size_includes_indices() {
+        let mut state = MemoryState::default();
+        state.add_key(&[0], None);
+        assert_eq!(state.deep_size_of(), 0);
+
+        let row: Vec<DataType> = vec![10.into(), "Cat".into()];
+        insert(&mut state, row.clone());
+        let one_index = state.deep_size_of();
+        assert!(one_index > row.deep_size_of());
+
+        state.add_key(&[1], None);
+        let two_indices = state.deep_size_of();
+        assert!(two_indices > one_index);
+
+        state.process_records(&mut vec![(row, false)].into(), None);
+        assert!(state.deep_size_of() < two_indices);
+    }
+
+    #[test]
+    fn memory_state_range_lookup() {
+        let mut state = MemoryState::default();
+        state.add_key(&[0], None);
+        for i in 0..5 {
+            insert(&mut state, vec![i.into(), (10 - i).into()]);
+        }
+
+        // existing rows must make it into the new index
+        state.add_ordered_key(1);
+        insert(&mut state, vec![5.into(), 5.into()]);
+
+        let range = (Bound::Included(6.into()), Bound::Excluded(9.into()));
+        let rows: Vec<_> = state.lookup_range(1, range).into_iter().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(&*rows[0], &[DataType::from(4), 6.into()][..]);
+        assert_eq!(&*rows[2], &[DataType::from(2), 8.into()][..]);
+
+        state.process_records(&mut vec![(vec![3.into(), 7.into()], false)].into(), None);
+        let range = (Bound::Unbounded, Bound::Included(7.into()));
+        let rows: Vec<_> = state.lookup_range(1, range).into_iter().collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&*rows[1], &[DataType::from(4), 6.into()][..]);
+
+        // point lookups still go to the hash index
+        match state.lookup(&[0], &KeyType::Single(&5.into())) {
+            LookupResult::Some(RecordResult::Borrowed(rows)) => assert_eq!(rows.len(), 1),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn memory_state_old_records_new_index() {
         let mut state = MemoryState::default();
@@ -107,3 +107,14 @@ impl<A: Clone> MakeKey<A> for (A, A, A, A, A, A) {
         )
     }
 }
+
+impl<A: Clone> MakeKey<A> for Vec<A> {
+    #[inline(always)]
+    fn from_row(key: &[usize], row: &[A]) -> Self {
+        key.iter().map(|&k| row[k].clone()).collect()
+    }
+    #[inline(always)]
+    fn from_key(key: &[A]) -> Self {
+        Vec::from(key)
+    }
+}
@@ -5,7 +5,7 @@ mod persistent_state;
 mod single_state;
 
 use std::borrow::Cow;
-use std::ops::Deref;
+use std::ops::{Bound, Deref};
 use std::rc::Rc;
 use std::vec;
 
@@ -37,6 +37,20 @@ pub(crate) trait State: SizeOf + Send {
 
     fn lookup<'a>(&'a self, columns: &[usize], key: &KeyType) -> LookupResult<'a>;
 
+    /// Add an index on `column` that keeps its keys ordered, so that it can serve
+    /// `lookup_range`. Ordered indices are always fully materialized, and are only supported by
+    /// in-memory state.
+    fn add_ordered_key(&mut self, column: usize);
+
+    /// Return all rows whose value in `column` falls within `range`, ordered by that value.
+    ///
+    /// Panics if no ordered index was added for `column`.
+    fn lookup_range<'a>(
+        &'a self,
+        column: usize,
+        range: (Bound<DataType>, Bound<DataType>),
+    ) -> RecordResult<'a>;
+
     fn rows(&self) -> usize;
 
     fn keys(&self) -> Vec<Vec<usize>>;
@@ -44,9 +58,9 @@ pub(crate) trait State: SizeOf + Send {
     /// Return a copy of all records. Panics if the state is only partially materialized.
     fn cloned_records(&self) -> Vec<Vec<DataType>>;
 
-    /// Evict `count` randomly selected keys, returning key colunms of the index chosen to evict
-    /// from along with the keys evicted and the number of bytes evicted.
-    fn evict_random_keys(&mut self, count: usize) -> (&[usize], Vec<Vec<DataType>>, u64);
+    /// Evict up to `count` keys that have not been read recently, returning key colunms of the
+    /// index chosen to evict from along with the keys evicted and the number of bytes evicted.
+    fn evict_least_recent_keys(&mut self, count: usize) -> (&[usize], Vec<Vec<DataType>>, u64);
 
     /// Evict the listed keys from the materialization targeted by `tag`, returning the key columns
     /// of the index that was evicted from and the number of bytes evicted.
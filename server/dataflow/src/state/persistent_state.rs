@@ -2,6 +2,7 @@ use bincode;
 use itertools::Itertools;
 use rocksdb::{self, PlainTableFactoryOptions, SliceTransform, WriteBatch};
 use serde;
+use std::path::PathBuf;
 use tempfile::{tempdir, TempDir};
 
 use crate::prelude::*;
@@ -53,6 +54,9 @@ pub struct PersistentState {
     seq: IndexSeq,
     epoch: IndexEpoch,
     has_unique_index: bool,
+    // Whether writes have to be synced to RocksDB's WAL before they are acknowledged. This is only
+    // needed with DurabilityMode::Permanent, since the files are discarded on exit otherwise.
+    sync_writes: bool,
     // With DurabilityMode::DeleteOnExit,
     // RocksDB files are stored in a temporary directory.
     _directory: Option<TempDir>,
@@ -77,9 +81,10 @@ impl State for PersistentState {
             }
         }
 
-        // Sync the writes to RocksDB's WAL:
+        // Sync the writes to RocksDB's WAL. Writes to a base are batched by its domain's
+        // GroupCommitQueueSet, so this is a single fsync for all the packets in a group commit.
         let mut opts = rocksdb::WriteOptions::default();
-        opts.set_sync(true);
+        opts.set_sync(self.sync_writes);
         tokio::task::block_in_place(|| self.db.as_ref().unwrap().write_opt(batch, &opts)).unwrap();
     }
 
@@ -114,6 +119,19 @@ impl State for PersistentState {
         })
     }
 
+    fn add_ordered_key(&mut self, _: usize) {
+        // RocksDB keys are not ordered by value, so a range lookup would have to scan every row
+        unimplemented!("persistent state does not support ordered indices");
+    }
+
+    fn lookup_range<'a>(
+        &'a self,
+        _: usize,
+        _: (std::ops::Bound<DataType>, std::ops::Bound<DataType>),
+    ) -> RecordResult<'a> {
+        unreachable!("range lookup on persistent state");
+    }
+
     fn add_key(&mut self, columns: &[usize], partial: Option<Vec<Tag>>) {
         assert!(partial.is_none(), "Bases can't be partial");
         let existing = self
@@ -204,7 +222,7 @@ impl State for PersistentState {
         unreachable!("PersistentState can't be partial")
     }
 
-    fn evict_random_keys(&mut self, _: usize) -> (&[usize], Vec<Vec<DataType>>, u64) {
+    fn evict_least_recent_keys(&mut self, _: usize) -> (&[usize], Vec<Vec<DataType>>, u64) {
         unreachable!("can't evict keys from PersistentState")
     }
 
@@ -226,7 +244,13 @@ impl PersistentState {
         tokio::task::block_in_place(|| {
             use rocksdb::{ColumnFamilyDescriptor, DB};
             let (directory, full_name) = match params.mode {
-                DurabilityMode::Permanent => (None, format!("{}.db", name)),
+                DurabilityMode::Permanent => {
+                    let path = match params.db_dir {
+                        Some(ref dir) => dir.join(&name),
+                        None => PathBuf::from(&name),
+                    };
+                    (None, format!("{}.db", path.to_str().unwrap()))
+                }
                 _ => {
                     let dir = tempdir().unwrap();
                     let path = dir.path().join(name.clone());
@@ -284,6 +308,7 @@ impl PersistentState {
                 seq: 0,
                 indices,
                 has_unique_index: primary_key.is_some(),
+                sync_writes: params.mode == DurabilityMode::Permanent,
                 epoch: meta.epoch,
                 db_opts: opts,
                 db: Some(db),
@@ -431,6 +456,7 @@ impl PersistentState {
             KeyType::Quad(k) => serialize(k, extra),
             KeyType::Quin(k) => serialize(k, extra),
             KeyType::Sex(k) => serialize(k, extra),
+            KeyType::Multi(k) => serialize(k, extra),
         }
     }
 
@@ -614,7 +640,6 @@ impl SizeOf for PersistentState {
 mod tests {
     use super::*;
     use bincode;
-    use std::path::PathBuf;
 
     fn insert<S: State>(state: &mut S, row: Vec<DataType>) {
         let record: Record = row.into();
@@ -880,6 +905,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn persistent_state_recover_from_db_dir() {
+        let dir = tempdir().unwrap();
+        let mut params = PersistenceParameters::default();
+        params.mode = DurabilityMode::Permanent;
+        params.db_dir = Some(dir.path().to_path_buf());
+        let row: Vec<DataType> = vec![10.into(), "Cat".into()];
+        {
+            let mut state = PersistentState::new(String::from("soup"), None, &params);
+            state.add_key(&[0], None);
+            insert(&mut state, row.clone());
+        }
+
+        assert!(dir.path().join("soup.db").exists());
+        let state = PersistentState::new(String::from("soup"), None, &params);
+        match state.lookup(&[0], &KeyType::Single(&10.into())) {
+            LookupResult::Some(RecordResult::Owned(rows)) => assert_eq!(rows, vec![row]),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn persistent_state_recover_unique_key() {
         let (_dir, name) = get_tmp_path();
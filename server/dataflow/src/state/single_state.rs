@@ -1,15 +1,29 @@
 use super::mk_key::MakeKey;
 use crate::prelude::*;
 use crate::state::keyed_state::KeyedState;
+use ahash::RandomState;
 use common::SizeOf;
 use rand::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::ops::Bound;
 use std::rc::Rc;
 
+/// Number of keys sampled for every key evicted by `SingleState::evict_least_recent_keys`.
+const EVICTION_SAMPLES: usize = 4;
+
 pub(super) struct SingleState {
     key: Vec<usize>,
     state: KeyedState,
     partial: bool,
     rows: usize,
+
+    /// Logical time of the most recent read of each filled key, indexed by the hash of the key.
+    /// Only maintained for partial state, since that is the only state we evict from.
+    last_read: RefCell<HashMap<u64, u64>>,
+    clock: Cell<u64>,
+    hasher: RandomState,
 }
 
 macro_rules! insert_row_match_impl {
@@ -44,7 +58,46 @@ impl SingleState {
             state: columns.into(),
             partial,
             rows: 0,
+            last_read: RefCell::default(),
+            clock: Cell::new(0),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Creates a fully materialized index on `column` that keeps its keys sorted.
+    pub(super) fn new_ordered(column: usize) -> Self {
+        Self {
+            state: KeyedState::Ordered(BTreeMap::new()),
+            ..Self::new(&[column], false)
+        }
+    }
+
+    fn hash_key(&self, key: &[DataType]) -> u64 {
+        let mut h = self.hasher.build_hasher();
+        key.iter().for_each(|k| k.hash(&mut h));
+        h.finish()
+    }
+
+    /// Hashes `key` the same way `hash_key` hashes the equivalent slice of values.
+    fn hash_key_type(&self, key: &KeyType) -> u64 {
+        let mut h = self.hasher.build_hasher();
+        match *key {
+            KeyType::Single(k) => k.hash(&mut h),
+            KeyType::Double(ref k) => k.hash(&mut h),
+            KeyType::Tri(ref k) => k.hash(&mut h),
+            KeyType::Quad(ref k) => k.hash(&mut h),
+            KeyType::Quin(ref k) => k.hash(&mut h),
+            KeyType::Sex(ref k) => k.hash(&mut h),
+            KeyType::Multi(ref k) => k.iter().for_each(|k| k.hash(&mut h)),
         }
+        h.finish()
+    }
+
+    /// Record that the key with the given hash was just read.
+    fn touch(&self, hash: u64) {
+        let now = self.clock.get() + 1;
+        self.clock.set(now);
+        self.last_read.borrow_mut().insert(hash, now);
     }
 
     /// Inserts the given record, or returns false if a hole was encountered (and the record hence
@@ -72,6 +125,16 @@ impl SingleState {
             KeyedState::Quad(ref mut map) => insert_row_match_impl!(self, r, map),
             KeyedState::Quin(ref mut map) => insert_row_match_impl!(self, r, map),
             KeyedState::Sex(ref mut map) => insert_row_match_impl!(self, r, map),
+            KeyedState::Multi(ref mut map) => insert_row_match_impl!(self, r, map),
+            KeyedState::Ordered(ref mut map) => {
+                // ordered indices are never partial
+                if let Some(ref mut rs) = map.get_mut(&r[self.key[0]]) {
+                    self.rows += 1;
+                    rs.insert(r);
+                    return true;
+                }
+                map.insert(r[self.key[0]].clone(), std::iter::once(r).collect());
+            }
         }
 
         self.rows += 1;
@@ -127,11 +190,22 @@ impl SingleState {
             KeyedState::Sex(ref mut map) => {
                 remove_row_match_impl!(self, r, do_remove, map, (DataType, _, _, _, _, _))
             }
+            KeyedState::Multi(ref mut map) => {
+                remove_row_match_impl!(self, r, do_remove, map, Vec<DataType>)
+            }
+            KeyedState::Ordered(ref mut map) => {
+                if let Some(ref mut rs) = map.get_mut(&r[self.key[0]]) {
+                    return do_remove(&mut self.rows, rs);
+                }
+            }
         }
         None
     }
 
     pub(super) fn mark_filled(&mut self, key: Vec<DataType>) {
+        // keys are filled because someone tried to read them
+        self.touch(self.hash_key(&key));
+
         let mut key = key.into_iter();
         let replaced = match self.state {
             KeyedState::Single(ref mut map) => map.insert(key.next().unwrap(), Rows::default()),
@@ -176,11 +250,15 @@ impl SingleState {
                 ),
                 Rows::default(),
             ),
+            KeyedState::Multi(ref mut map) => map.insert(key.collect(), Rows::default()),
+            KeyedState::Ordered(ref mut map) => map.insert(key.next().unwrap(), Rows::default()),
         };
         assert!(replaced.is_none());
     }
 
     pub(super) fn mark_hole(&mut self, key: &[DataType]) -> u64 {
+        let hash = self.hash_key(key);
+        self.last_read.get_mut().remove(&hash);
         let removed = match self.state {
             KeyedState::Single(ref mut m) => m.swap_remove(&(key[0])),
             KeyedState::Double(ref mut m) => {
@@ -198,6 +276,8 @@ impl SingleState {
             KeyedState::Sex(ref mut m) => {
                 m.swap_remove::<(DataType, _, _, _, _, _)>(&MakeKey::from_key(key))
             }
+            KeyedState::Multi(ref mut m) => m.swap_remove(key),
+            KeyedState::Ordered(ref mut m) => m.remove(&key[0]),
         };
         // mark_hole should only be called on keys we called mark_filled on
         removed
@@ -210,6 +290,7 @@ impl SingleState {
 
     pub(super) fn clear(&mut self) {
         self.rows = 0;
+        self.last_read.get_mut().clear();
         match self.state {
             KeyedState::Single(ref mut map) => map.clear(),
             KeyedState::Double(ref mut map) => map.clear(),
@@ -217,32 +298,65 @@ impl SingleState {
             KeyedState::Quad(ref mut map) => map.clear(),
             KeyedState::Quin(ref mut map) => map.clear(),
             KeyedState::Sex(ref mut map) => map.clear(),
+            KeyedState::Multi(ref mut map) => map.clear(),
+            KeyedState::Ordered(ref mut map) => map.clear(),
         };
     }
 
-    /// Evict `count` randomly selected keys from state and return them along with the number of
-    /// bytes freed.
-    pub(super) fn evict_random_keys(
+    /// Evict up to `count` keys that have not been read recently from state and return them along
+    /// with the number of bytes freed.
+    ///
+    /// Keeping all keys ordered by their last read would make every read more expensive, so this
+    /// instead samples a few keys for every key to evict, and evicts the least recently read ones.
+    pub(super) fn evict_least_recent_keys(
         &mut self,
         count: usize,
         rng: &mut ThreadRng,
     ) -> (u64, Vec<Vec<DataType>>) {
+        let len = self.state.len();
+        let mut indices: Vec<usize> = if len <= count * EVICTION_SAMPLES {
+            (0..len).collect()
+        } else {
+            (0..count * EVICTION_SAMPLES)
+                .map(|_| rng.gen_range(0, len))
+                .collect()
+        };
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut candidates: Vec<_> = {
+            let last_read = self.last_read.borrow();
+            indices
+                .into_iter()
+                .map(|i| {
+                    let key = self.state.key_at(i).unwrap();
+                    let hash = self.hash_key(&key);
+                    (last_read.get(&hash).cloned().unwrap_or(0), hash, key)
+                })
+                .collect()
+        };
+        candidates.sort_unstable_by_key(|&(read, _, _)| read);
+        candidates.truncate(count);
+
         let mut bytes_freed = 0;
-        let mut keys = Vec::with_capacity(count);
-        for _ in 0..count {
-            if let Some((n, key)) = self.state.evict_with_seed(rng.gen()) {
-                bytes_freed += n;
-                keys.push(key);
-            } else {
-                break;
-            }
+        let mut keys = Vec::with_capacity(candidates.len());
+        for (_, hash, key) in candidates {
+            self.last_read.get_mut().remove(&hash);
+            bytes_freed += self.state.evict(&key);
+            keys.push(key);
         }
         (bytes_freed, keys)
     }
 
     /// Evicts a specified key from this state, returning the number of bytes freed.
     pub(super) fn evict_keys(&mut self, keys: &[Vec<DataType>]) -> u64 {
-        keys.iter().map(|k| self.state.evict(k)).sum()
+        keys.iter()
+            .map(|k| {
+                let hash = self.hash_key(k);
+                self.last_read.get_mut().remove(&hash);
+                self.state.evict(k)
+            })
+            .sum()
     }
 
     pub(super) fn values<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Rows> + 'a> {
@@ -253,6 +367,26 @@ impl SingleState {
             KeyedState::Quad(ref map) => Box::new(map.values()),
             KeyedState::Quin(ref map) => Box::new(map.values()),
             KeyedState::Sex(ref map) => Box::new(map.values()),
+            KeyedState::Multi(ref map) => Box::new(map.values()),
+            KeyedState::Ordered(ref map) => Box::new(map.values()),
+        }
+    }
+    /// Returns the rows for all keys that fall within the given bounds, in key order.
+    ///
+    /// Panics if this is not an ordered index, or if the bounds describe an invalid range.
+    pub(super) fn lookup_range<'a>(
+        &'a self,
+        range: (Bound<DataType>, Bound<DataType>),
+    ) -> impl Iterator<Item = &'a Rows> + 'a {
+        match self.state {
+            KeyedState::Ordered(ref map) => map.range(range).map(|(_, rs)| rs),
+            _ => unreachable!("range lookup on unordered index"),
+        }
+    }
+    pub(super) fn is_ordered(&self) -> bool {
+        match self.state {
+            KeyedState::Ordered(..) => true,
+            _ => false,
         }
     }
     pub(super) fn key(&self) -> &[usize] {
@@ -267,8 +401,25 @@ impl SingleState {
     pub(super) fn is_empty(&self) -> bool {
         self.rows == 0
     }
+
+    /// Estimate the number of bytes used by this index itself, excluding the rows it points to.
+    ///
+    /// This is only an estimate (hash table load factors are not accounted for), but it is cheap
+    /// to compute, which matters since it is consulted every time we check for memory pressure.
+    pub(super) fn index_size(&self) -> u64 {
+        use std::mem::size_of;
+
+        let key_size = self.key.len() * size_of::<DataType>();
+        let entries = self.state.len() * (key_size + size_of::<Rows>());
+        let rows = self.rows * (size_of::<Row>() + size_of::<usize>());
+        let reads = self.last_read.borrow().len() * 2 * size_of::<u64>();
+        (entries + rows + reads) as u64
+    }
     pub(super) fn lookup<'a>(&'a self, key: &KeyType) -> LookupResult<'a> {
         if let Some(rs) = self.state.lookup(key) {
+            if self.partial {
+                self.touch(self.hash_key_type(key));
+            }
             LookupResult::Some(RecordResult::Borrowed(rs))
         } else if self.partial() {
             // partially materialized, so this is a hole (empty results would be vec![])
@@ -806,6 +806,7 @@ impl Debug for MirNodeType {
             } => {
                 let op_string = match *kind {
                     AggregationKind::COUNT => format!("|*|({})", on.name.as_str()),
+                    AggregationKind::COUNT_STAR => String::from("|*|(*)"),
                     AggregationKind::SUM => format!("𝛴({})", on.name.as_str()),
                 };
                 let group_cols = group_by
@@ -874,6 +875,11 @@ impl Debug for MirNodeType {
                                     .collect::<Vec<_>>()
                                     .join(", ")
                             )),
+                            FilterCondition::Distance { .. }
+                            | FilterCondition::Between(..)
+                            | FilterCondition::And(..)
+                            | FilterCondition::Or(..)
+                            | FilterCondition::Not(..) => Some(escape(&cond.describe(*i))),
                         })
                         .collect::<Vec<_>>()
                         .as_slice()
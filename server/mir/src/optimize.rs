@@ -170,7 +170,7 @@ fn find_and_merge_filter_aggregates(q: &mut MirQuery) -> Vec<MirNodeRef> {
                     on.clone(),
                     group_by.to_vec(),
                     match kind {
-                        Aggregation::COUNT => FilterAggregation::COUNT,
+                        Aggregation::COUNT | Aggregation::COUNT_STAR => FilterAggregation::COUNT,
                         Aggregation::SUM => FilterAggregation::SUM,
                     },
                 )
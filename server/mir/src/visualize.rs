@@ -106,6 +106,7 @@ impl GraphViz for MirNodeType {
             } => {
                 let op_string = match *kind {
                     AggregationKind::COUNT => format!("\\|*\\|({})", print_col(on)),
+                    AggregationKind::COUNT_STAR => String::from("\\|*\\|(*)"),
                     AggregationKind::SUM => format!("𝛴({})", print_col(on)),
                 };
                 let group_cols = group_by
@@ -193,6 +194,11 @@ impl GraphViz for MirNodeType {
                                     .collect::<Vec<_>>()
                                     .join(", ")
                             )),
+                            FilterCondition::Distance { .. }
+                            | FilterCondition::Between(..)
+                            | FilterCondition::And(..)
+                            | FilterCondition::Or(..)
+                            | FilterCondition::Not(..) => Some(escape(&cond.describe(*i))),
                         })
                         .collect::<Vec<_>>()
                         .as_slice()
@@ -43,6 +43,18 @@ impl Builder {
         self.config.domain_config.replay_batch_timeout = t;
     }
 
+    /// Log a warning, along with a sample record, whenever a node takes longer than `t` to
+    /// process a single update.
+    pub fn set_slow_update_threshold(&mut self, t: time::Duration) {
+        self.config.domain_config.slow_update_threshold = Some(t);
+    }
+
+    /// Log a warning, along with a sample record, whenever a node turns a single update into more
+    /// than `n` output records.
+    pub fn set_large_fanout_threshold(&mut self, n: usize) {
+        self.config.domain_config.large_fanout_threshold = Some(n);
+    }
+
     /// Set the persistence parameters used by the system.
     pub fn set_persistence(&mut self, p: PersistenceParameters) {
         self.config.persistence = p;
@@ -93,6 +105,19 @@ impl Builder {
         self.config.reuse = reuse_type;
     }
 
+    /// Set how many packets a domain may have waiting to be sent to other domains before it stops
+    /// taking in new work (default is 4096).
+    ///
+    /// Domains that fall behind then push back on the domains that feed them, and eventually on
+    /// the clients writing to base tables.
+    pub fn set_outbox_high_water(&mut self, packets: usize) {
+        assert!(
+            packets > 0,
+            "outboxes must be able to hold at least one packet"
+        );
+        self.config.outbox_high_water = packets;
+    }
+
     /// Set the number of pool threads to use (default is #cores)
     pub fn set_threads(&mut self, threads: usize) {
         self.config.threads = Some(threads);
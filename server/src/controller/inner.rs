@@ -13,7 +13,9 @@ use nom_sql::ColumnSpecification;
 use noria::builders::*;
 use noria::channel::tcp::{SendError, TcpSender};
 use noria::consensus::{Authority, Epoch, STATE_KEY};
+use noria::debug::explain::Plan;
 use noria::debug::stats::{DomainStats, GraphStats, NodeStats};
+use noria::debug::trace::TraceEvent;
 use noria::ActivationResult;
 use petgraph::visit::Bfs;
 use slog::Logger;
@@ -110,6 +112,17 @@ impl DomainReplies {
         }
         stats
     }
+
+    async fn wait_for_trace(&mut self, d: &DomainHandle) -> Vec<TraceEvent> {
+        let mut events = Vec::new();
+        for r in self.read_n_domain_replies(d.shards()).await {
+            match r {
+                ControlReplyPacket::Trace(es) => events.extend(es),
+                r => unreachable!("got unexpected non-trace control reply: {:?}", r),
+            }
+        }
+        events
+    }
 }
 
 pub(super) fn graphviz(
@@ -289,6 +302,12 @@ impl ControllerInner {
                     self.create_universe(args)
                         .map(|r| json::to_string(&r).unwrap())
                 }),
+            (Method::POST, "/explain") => json::from_slice(&body)
+                .map_err(|_| StatusCode::BAD_REQUEST)
+                .map(|args| self.explain(args).map(|r| json::to_string(&r).unwrap())),
+            (Method::POST, "/trace") => json::from_slice(&body)
+                .map_err(|_| StatusCode::BAD_REQUEST)
+                .map(|args| Ok(json::to_string(&self.trace(args)).unwrap())),
             (Method::POST, "/remove_node") => json::from_slice(&body)
                 .map_err(|_| StatusCode::BAD_REQUEST)
                 .map(|args| {
@@ -659,6 +678,7 @@ impl ControllerInner {
             added: Default::default(),
             columns: Default::default(),
             readers: Default::default(),
+            placements: Default::default(),
             context,
             start: time::Instant::now(),
             log: miglog,
@@ -681,6 +701,7 @@ impl ControllerInner {
             added: Default::default(),
             columns: Default::default(),
             readers: Default::default(),
+            placements: Default::default(),
             context: Default::default(),
             start: time::Instant::now(),
             log: miglog,
@@ -771,6 +792,11 @@ impl ControllerInner {
             let domain = self.ingredients[r].domain();
             let columns = self.ingredients[r].fields().to_vec();
             let schema = self.view_schema(r);
+            let key = self.ingredients[r]
+                .with_reader(|r| r.key().map(Vec::from))
+                .ok()
+                .flatten()
+                .unwrap_or_default();
             let shards = (0..self.domains[&domain].shards())
                 .map(|i| self.read_addrs[&self.domains[&domain].assignment(i)])
                 .collect();
@@ -779,6 +805,7 @@ impl ControllerInner {
                 node: r,
                 columns,
                 schema,
+                key,
                 shards,
             }
         })
@@ -885,6 +912,90 @@ impl ControllerInner {
         GraphStats { domains }
     }
 
+    /// Collect the events all domains have recorded for writes with the given trace id.
+    fn trace(&mut self, trace: u64) -> Vec<TraceEvent> {
+        let workers = &self.workers;
+        let replies = &mut self.replies;
+        let mut events: Vec<_> = self
+            .domains
+            .values_mut()
+            .flat_map(|s| {
+                s.send_to_healthy(Box::new(Packet::GetTrace { trace }), workers)
+                    .unwrap();
+                futures_executor::block_on(replies.wait_for_trace(&s))
+            })
+            .collect();
+        events.sort_by_key(|e| e.at);
+        events
+    }
+
+    fn explain(&mut self, view: String) -> Result<Plan, String> {
+        let node = match self.recipe.node_addr_for(&view) {
+            Ok(ni) => ni,
+            Err(_) => *self
+                .outputs()
+                .get(&view)
+                .ok_or_else(|| format!("no view named {}", view))?,
+        };
+        let name = self.recipe.resolve_alias(&view).unwrap_or(&view).to_owned();
+
+        let mut mem_sizes: HashMap<NodeIndex, u64> = HashMap::new();
+        for (_, nodes) in self.get_statistics().domains.values() {
+            for (&ni, ns) in nodes {
+                *mem_sizes.entry(ni).or_default() += ns.mem_size;
+            }
+        }
+
+        // the view's reader holds the materialization clients actually read from
+        let reader = self.find_view_for(node, &name);
+        let mut plan = self
+            .explain_node(node, &mem_sizes)
+            .pop()
+            .expect("views are computed by an operator");
+        if let Some(reader) = reader {
+            let n = &self.ingredients[reader];
+            plan.materialized = self.materializations.get_status(reader, n);
+            if let Some(key) = n.with_reader(|r| r.key().map(Vec::from)).unwrap() {
+                plan.indices = vec![key];
+            }
+            plan.mem_size += mem_sizes.get(&reader).cloned().unwrap_or(0);
+        }
+        Ok(plan)
+    }
+
+    /// Describe `ni` and its ancestors, skipping over nodes that only move data between domains.
+    fn explain_node(&self, ni: NodeIndex, mem_sizes: &HashMap<NodeIndex, u64>) -> Vec<Plan> {
+        let n = &self.ingredients[ni];
+        let mut parents = Vec::new();
+        for p in self
+            .ingredients
+            .neighbors_directed(ni, petgraph::EdgeDirection::Incoming)
+        {
+            if !self.ingredients[p].is_source() {
+                parents.extend(self.explain_node(p, mem_sizes));
+            }
+        }
+
+        if !n.is_internal() && !n.is_base() {
+            return parents;
+        }
+
+        vec![Plan {
+            node: ni,
+            name: n.name().to_owned(),
+            description: if n.is_base() {
+                "Base table".to_owned()
+            } else {
+                n.description(true)
+            },
+            domain: n.domain().index(),
+            materialized: self.materializations.get_status(ni, n),
+            indices: self.materializations.indices_for(ni),
+            mem_size: mem_sizes.get(&ni).cloned().unwrap_or(0),
+            parents,
+        }]
+    }
+
     fn get_instances(&self) -> Vec<(WorkerIdentifier, bool, Duration)> {
         self.workers
             .iter()
@@ -3,8 +3,24 @@
 use dataflow::prelude::*;
 use petgraph;
 use slog::Logger;
+use std::collections::HashMap;
+
+/// An explicit placement for a new node, overriding the assignment heuristic below.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Placement {
+    /// Place the node in a new domain of its own.
+    Isolated,
+    /// Place the node in the same domain as the given node.
+    With(NodeIndex),
+}
 
-pub fn assign(log: &Logger, graph: &mut Graph, topo_list: &[NodeIndex], ndomains: &mut usize) {
+pub fn assign(
+    log: &Logger,
+    graph: &mut Graph,
+    topo_list: &[NodeIndex],
+    placements: &HashMap<NodeIndex, Placement>,
+    ndomains: &mut usize,
+) {
     // we need to walk the data flow graph and assign domains to all new nodes.
     // we generally want as few domains as possible, but in *some* cases we must make new ones.
     // specifically:
@@ -23,6 +39,18 @@ pub fn assign(log: &Logger, graph: &mut Graph, topo_list: &[NodeIndex], ndomains
             let graph = &*graph;
             let n = &graph[node];
 
+            match placements.get(&node) {
+                Some(Placement::Isolated) => return next_domain(),
+                Some(&Placement::With(other)) => {
+                    assert!(
+                        graph[other].has_domain(),
+                        "node must be placed with a node that has already been assigned a domain"
+                    );
+                    return graph[other].domain().index();
+                }
+                None => {}
+            }
+
             // TODO: the code below is probably _too_ good at keeping things in one domain.
             // having all bases in one domain (e.g., if sharding is disabled) isn't great because
             // write performance will suffer terribly.
@@ -52,6 +52,9 @@ pub(in crate::controller) struct Materializations {
     have: HashMap<NodeIndex, Indices>,
     added: HashMap<NodeIndex, Indices>,
 
+    ordered: HashMap<NodeIndex, HashSet<usize>>,
+    added_ordered: HashMap<NodeIndex, Vec<usize>>,
+
     partial: HashSet<NodeIndex>,
     partial_enabled: bool,
     frontier_strategy: FrontierStrategy,
@@ -68,6 +71,9 @@ impl Materializations {
             have: HashMap::default(),
             added: HashMap::default(),
 
+            ordered: HashMap::default(),
+            added_ordered: HashMap::default(),
+
             partial: HashSet::default(),
             partial_enabled: true,
             frontier_strategy: FrontierStrategy::None,
@@ -162,6 +168,41 @@ impl Materializations {
                     .collect()
             };
 
+            // ordered indices are never hoisted, since range lookups can't be answered by
+            // querying through. they also need a regular index on the same column so that the
+            // node's state can be replayed.
+            for (ni, cols) in n.suggest_ordered_indexes(ni) {
+                // base tables are always kept in persistent state, which can't do range lookups
+                if graph[ni].is_base() {
+                    crit!(self.log, "ordered index on base table";
+                          "node" => ni.index(),
+                          "graph" => graphviz(graph, true, &self));
+                    panic!(
+                        "cannot add ordered index on {:?} to base table {}: range lookups can \
+                         only be served by non-base nodes",
+                        cols,
+                        graph[ni].name()
+                    );
+                }
+
+                for col in cols {
+                    trace!(self.log, "new ordered indexing obligation";
+                           "node" => ni.index(),
+                           "column" => col);
+
+                    if self.ordered.entry(ni).or_default().insert(col) {
+                        self.added_ordered.entry(ni).or_default().push(col);
+                    }
+                    if self.have.entry(ni).or_default().insert(vec![col]) {
+                        replay_obligations
+                            .entry(ni)
+                            .or_insert_with(HashSet::new)
+                            .insert(vec![col]);
+                        self.added.entry(ni).or_default().insert(vec![col]);
+                    }
+                }
+            }
+
             if indices.is_empty() && n.is_base() {
                 // we must *always* materialize base nodes
                 // so, just make up some column to index on
@@ -335,6 +376,12 @@ impl Materializations {
                 able = false;
             }
 
+            // range lookups can't miss, so ordered indices need all the rows
+            if self.ordered.contains_key(&ni) {
+                warn!(self.log, "full because ordered"; "node" => ni.index());
+                able = false;
+            }
+
             // we are already fully materialized, so can't be made partial
             if !new.contains(&ni)
                 && self.added.get(&ni).map(|i| i.len()).unwrap_or(0)
@@ -452,6 +499,53 @@ impl Materializations {
         assert!(replay_obligations.is_empty());
     }
 
+    /// Describes the materialization decisions made for the given new nodes, and any indices added
+    /// to existing materializations, in a human-readable form with one node per line.
+    fn describe_plan(&self, graph: &Graph, new: &HashSet<NodeIndex>) -> String {
+        let sorted = |indices: &Indices| {
+            let mut indices: Vec<_> = indices.iter().cloned().collect();
+            indices.sort();
+            indices
+        };
+
+        let mut nodes: Vec<_> = new.iter().chain(self.added.keys()).cloned().collect();
+        nodes.sort();
+        nodes.dedup();
+        nodes
+            .into_iter()
+            .map(|ni| {
+                let n = &graph[ni];
+                let kind = if self.partial.contains(&ni) {
+                    "partial"
+                } else {
+                    "full"
+                };
+                let decision = if !new.contains(&ni) {
+                    format!("add indices {:?}", sorted(&self.added[&ni]))
+                } else if let Some(indices) = self.have.get(&ni) {
+                    format!("{} on {:?}", kind, sorted(indices))
+                } else if let Ok(Some(key)) = n.with_reader(|r| r.key()) {
+                    format!("{} reader on {:?}", kind, key)
+                } else {
+                    String::from("not materialized")
+                };
+                format!("{} [{}]: {}", n.name(), ni.index(), decision)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The indices that have been added to the given node's state, in a stable order.
+    pub(in crate::controller) fn indices_for(&self, index: NodeIndex) -> Vec<Vec<usize>> {
+        let mut indices: Vec<_> = self
+            .have
+            .get(&index)
+            .map(|is| is.iter().cloned().collect())
+            .unwrap_or_default();
+        indices.sort();
+        indices
+    }
+
     /// Retrieves the materialization status of a given node, or None
     /// if the node isn't materialized.
     pub(in crate::controller) fn get_status(
@@ -487,6 +581,11 @@ impl Materializations {
         replies: &mut DomainReplies,
     ) {
         self.extend(graph, new);
+        debug!(
+            self.log,
+            "materialization plan:\n{}",
+            self.describe_plan(graph, new)
+        );
 
         // check that we don't have fully materialized nodes downstream of partially materialized
         // nodes.
@@ -513,15 +612,25 @@ impl Materializations {
                 }
 
                 if let Some(pi) = any_partial(self, graph, ni) {
-                    println!("{}", graphviz(graph, true, &self));
                     crit!(self.log, "partial materializations above full materialization";
                               "full" => ni.index(),
-                              "partial" => pi.index());
+                              "partial" => pi.index(),
+                              "graph" => graphviz(graph, true, &self));
                     unimplemented!();
                 }
             }
         }
 
+        // check that we don't add ordered indices to nodes that were already made partial
+        for &ni in self.added_ordered.keys() {
+            if self.partial.contains(&ni) {
+                crit!(self.log, "ordered index on partial materialization";
+                      "node" => ni.index(),
+                      "graph" => graphviz(graph, true, &self));
+                unimplemented!();
+            }
+        }
+
         // Mark nodes as beyond the frontier as dictated by the strategy
         for &ni in new {
             let n = graph.node_weight_mut(ni).unwrap();
@@ -595,13 +704,13 @@ impl Materializations {
                                                 .find(|c| !index.contains(&c))
                                         });
                                     if let Some(not_shared) = unshared {
-                                        println!("{}", graphviz(graph, true, &self));
                                         crit!(self.log, "partially overlapping partial indices";
                                                   "parent" => pni.index(),
                                                   "pcols" => ?index,
                                                   "child" => ni.index(),
                                                   "cols" => ?columns,
                                                   "conflict" => not_shared,
+                                                  "graph" => graphviz(graph, true, &self),
                                         );
                                         unimplemented!();
                                     }
@@ -716,7 +825,9 @@ impl Materializations {
         }
         while let Some(ni) = non_purge.pop() {
             if graph[ni].purge {
-                println!("{}", graphviz(graph, true, &self));
+                crit!(self.log, "found purge node above non-purge node";
+                      "node" => ni.index(),
+                      "graph" => graphviz(graph, true, &self));
                 assert!(
                     !graph[ni].purge,
                     "found purge node {} above non-purge node",
@@ -747,14 +858,14 @@ impl Materializations {
 
             if new.contains(&node) {
                 make.push(node);
-            } else if self.added.contains_key(&node) {
+            } else if self.added.contains_key(&node) || self.added_ordered.contains_key(&node) {
                 reindex.push(node);
             }
         }
 
         // first, we add any new indices to existing nodes
         for node in reindex {
-            let mut index_on = self.added.remove(&node).unwrap();
+            let mut index_on = self.added.remove(&node).unwrap_or_default();
 
             // are they trying to make a non-materialized node materialized?
             if self.have[&node] == index_on {
@@ -778,12 +889,12 @@ impl Materializations {
                             != self.have.get(&child).map(|i| i.len()).unwrap_or(0)
                         {
                             // node was previously materialized!
-                            println!("{}", graphviz(graph, true, &self));
                             crit!(
                                 self.log,
                                 "attempting to make old non-materialized node with children partial";
                                 "node" => node.index(),
                                 "child" => child.index(),
+                                "graph" => graphviz(graph, true, &self),
                             );
                             unimplemented!();
                         }
@@ -809,7 +920,7 @@ impl Materializations {
                 self.setup(node, &mut index_on, graph, domains, workers, replies);
                 self.log = log;
                 index_on.clear();
-            } else {
+            } else if !index_on.is_empty() {
                 use dataflow::payload::InitialState;
                 domains
                     .get_mut(&n.domain())
@@ -823,6 +934,8 @@ impl Materializations {
                     )
                     .unwrap();
             }
+
+            self.prepare_ordered(node, graph, domains, workers);
         }
 
         // then, we start prepping new nodes
@@ -861,6 +974,10 @@ impl Materializations {
             futures_executor::block_on(replies.wait_for_acks(&domain));
             trace!(self.log, "node ready"; "node" => ni.index());
 
+            // the node's state now holds all its rows, and none of its children have been readied
+            // yet, so this is when ordered indices can be built without missing any lookups.
+            self.prepare_ordered(ni, graph, domains, workers);
+
             if reconstructed {
                 info!(self.log, "reconstruction completed";
                 "ms" => start.elapsed().as_millis(),
@@ -870,6 +987,37 @@ impl Materializations {
         }
 
         self.added.clear();
+        assert!(self.added_ordered.is_empty());
+    }
+
+    /// Tell the domain of the given node to build any ordered indices newly added to it.
+    fn prepare_ordered(
+        &mut self,
+        ni: NodeIndex,
+        graph: &Graph,
+        domains: &mut HashMap<DomainIndex, DomainHandle>,
+        workers: &HashMap<WorkerIdentifier, Worker>,
+    ) {
+        use dataflow::payload::InitialState;
+
+        if let Some(columns) = self.added_ordered.remove(&ni) {
+            info!(self.log, "adding ordered indices to view";
+                  "node" => ni.index(),
+                  "columns" => ?columns);
+
+            let n = &graph[ni];
+            domains
+                .get_mut(&n.domain())
+                .unwrap()
+                .send_to_healthy(
+                    Box::new(Packet::PrepareState {
+                        node: n.local_addr(),
+                        state: InitialState::OrderedLocal(columns),
+                    }),
+                    workers,
+                )
+                .unwrap();
+        }
     }
 
     /// Perform all operations necessary to bring any materializations for the given node up, and
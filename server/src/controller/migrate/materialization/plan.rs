@@ -287,8 +287,8 @@ impl<'a> Plan<'a> {
                 //  a domain may appear multiple times in this list if a path crosses into the same
                 //  domain more than once. currently, that will cause a deadlock.
                 if seen.contains(&domain) {
-                    println!("{}", graphviz(&self.graph, true, &self.m));
-                    crit!(self.m.log, "detected a-b-a domain replay path");
+                    crit!(self.m.log, "detected a-b-a domain replay path";
+                          "graph" => graphviz(&self.graph, true, &self.m));
                     unimplemented!();
                 }
                 seen.insert(domain);
@@ -21,6 +21,7 @@
 //! Beware, Here be dragons™
 
 use crate::controller::ControllerInner;
+use dataflow::ops::filter::FilterCondition;
 use dataflow::prelude::*;
 use dataflow::{node, prelude::Packet};
 use std::collections::{HashMap, HashSet};
@@ -31,6 +32,8 @@ use slog;
 
 mod assignment;
 mod augmentation;
+
+pub(crate) use self::assignment::Placement;
 pub(crate) mod materialization;
 mod routing;
 mod sharding;
@@ -51,6 +54,7 @@ pub struct Migration<'a> {
     pub(super) added: HashSet<NodeIndex>,
     pub(super) columns: Vec<(NodeIndex, ColumnChange)>,
     pub(super) readers: HashMap<NodeIndex, NodeIndex>,
+    pub(super) placements: HashMap<NodeIndex, Placement>,
 
     pub(super) start: Instant,
     pub(super) log: slog::Logger,
@@ -277,6 +281,52 @@ impl<'a> Migration<'a> {
             .unwrap();
     }
 
+    /// Keep the rows for each key of the reader for the given node ordered by `column`.
+    ///
+    /// This allows clients to retrieve the first or last rows for a key (see
+    /// `View::lookup_limited`) without sorting them at read time. The reader must have been set
+    /// up with `maintain` or `maintain_anonymous` in this migration.
+    pub fn order_reader_by(&mut self, n: NodeIndex, column: usize) {
+        let ri = self.readers[&n];
+        assert!(self.added.contains(&ri));
+
+        self.mainline.ingredients[ri]
+            .with_reader_mut(|r| r.set_order(column))
+            .unwrap();
+    }
+
+    /// Only return the rows of the reader for the given node that match all of `conditions`.
+    ///
+    /// Unlike a filter in the graph, these conditions may refer to the arguments of each read
+    /// (see `View::lookup_with_args`), such as the point to measure distances from. The reader
+    /// must have been set up with `maintain` or `maintain_anonymous` in this migration.
+    pub fn filter_reader_by(&mut self, n: NodeIndex, conditions: &[(usize, FilterCondition)]) {
+        let ri = self.readers[&n];
+        assert!(self.added.contains(&ri));
+
+        self.mainline.ingredients[ri]
+            .with_reader_mut(|r| r.set_filter(conditions))
+            .unwrap();
+    }
+
+    /// Place the given new node in a domain of its own, instead of letting the migration choose
+    /// its domain.
+    pub fn place_in_new_domain(&mut self, n: NodeIndex) {
+        assert!(self.added.contains(&n));
+        self.placements.insert(n, Placement::Isolated);
+    }
+
+    /// Place the given new node in the same domain as `other`, instead of letting the migration
+    /// choose its domain.
+    ///
+    /// `other` must either be an existing node, or a new node that comes before `n` in the graph.
+    /// Placements are not checked for paths that leave a domain and then re-enter it, and should
+    /// not be used together with sharding.
+    pub fn place_with(&mut self, n: NodeIndex, other: NodeIndex) {
+        assert!(self.added.contains(&n));
+        self.placements.insert(n, Placement::With(other));
+    }
+
     /// Commit the changes introduced by this `Migration` to the master `Soup`.
     ///
     /// This will spin up an execution thread for each new thread domain, and hook those new
@@ -308,6 +358,7 @@ impl<'a> Migration<'a> {
             &log,
             &mut mainline.ingredients,
             &topo,
+            &self.placements,
             &mut mainline.ndomains,
         );
 
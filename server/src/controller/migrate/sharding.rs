@@ -176,6 +176,20 @@ pub fn shard(
                     // ok to continue since standard shard_by is None
                     continue;
                 }
+                None if graph[node]
+                    .get_base()
+                    .and_then(|b| b.auto_increment_column())
+                    .is_some() =>
+                {
+                    // shards would hand out the same ids, and a row's id isn't known until its
+                    // shard has been picked
+                    info!(log, "not sharding base node that assigns ids"; "node" => ?node);
+                    graph
+                        .node_weight_mut(node)
+                        .unwrap()
+                        .shard_by(Sharding::ForcedNone);
+                    continue;
+                }
                 None => {
                     // base nodes -- what do we shard them by?
                     warn!(log, "sharding base node"; "node" => ?node, "column" => want_sharding);
@@ -431,7 +431,25 @@ fn make_base_node(
         })
         .collect::<Vec<DataType>>();
 
-    let base = if !pkey_columns.is_empty() {
+    let declared_defaults = column_specs
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(ref cs, _))| {
+            cs.constraints.iter().any(|c| match *c {
+                ColumnConstraint::DefaultValue(_) => true,
+                _ => false,
+            })
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let auto_increment = column_specs.iter().position(|&(ref cs, _)| {
+        cs.constraints
+            .iter()
+            .any(|c| *c == ColumnConstraint::AutoIncrement)
+    });
+
+    let mut base = if !pkey_columns.is_empty() {
         let pkey_column_ids = pkey_columns
             .iter()
             .map(|pkc| {
@@ -446,6 +464,12 @@ fn make_base_node(
     } else {
         node::special::Base::new(default_values)
     };
+    if !declared_defaults.is_empty() {
+        base = base.with_declared_defaults(declared_defaults);
+    }
+    if let Some(column) = auto_increment {
+        base = base.with_auto_increment(column);
+    }
 
     FlowNode::New(mig.add_base(name, column_names.as_slice(), base))
 }
@@ -159,7 +159,8 @@ pub(super) async fn main<A: Authority + 'static>(
                 let c = campaign.take().unwrap();
                 tokio::task::block_in_place(move || c.join().unwrap());
                 let drx = drx.take().unwrap();
-                controller = Some(ControllerInner::new(log.clone(), state, drx));
+                let log = log.new(o!("epoch" => ?state.epoch));
+                controller = Some(ControllerInner::new(log, state, drx));
             }
             Event::CampaignError(e) => {
                 panic!("{:?}", e);
@@ -171,8 +172,7 @@ pub(super) async fn main<A: Authority + 'static>(
     // shutting down
     if controller.is_some() {
         if let Err(e) = authority.surrender_leadership() {
-            error!(log, "failed to surrender leadership");
-            eprintln!("{:?}", e);
+            error!(log, "failed to surrender leadership"; "error" => ?e);
         }
     }
 }
@@ -24,6 +24,11 @@ fn to_sql_type(d: &DataType) -> Option<SqlType> {
         // type), so caller must handle appropriately.
         DataType::None => None,
         DataType::Timestamp(_) => Some(SqlType::Timestamp),
+        DataType::Decimal(_, scale) => Some(SqlType::Decimal(18, *scale)),
+        DataType::Bytes(_) => Some(SqlType::Blob),
+        DataType::Uuid(_) => Some(SqlType::Binary(16)),
+        DataType::Bool(_) => Some(SqlType::Bool),
+        DataType::Float(_) => Some(SqlType::Double),
     }
 }
 
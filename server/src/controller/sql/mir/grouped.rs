@@ -1,4 +1,5 @@
 use crate::controller::sql::mir::SqlToMirConverter;
+use crate::controller::sql::passes::count_star_rewrite::count_star_column;
 use crate::controller::sql::query_graph::{QueryGraph, QueryGraphEdge};
 use mir::{Column, MirNodeRef};
 use nom_sql::FunctionExpression::*;
@@ -13,8 +14,11 @@ fn target_columns_from_computed_column(computed_col: &nom_sql::Column) -> Column
     use nom_sql::FunctionExpression::*;
 
     match *computed_col.function.as_ref().unwrap().deref() {
+        Count(FunctionArguments::Column(ref col), _) => match count_star_column(col) {
+            Some(ref col) => Column::from(col),
+            None => Column::from(col),
+        },
         Avg(FunctionArguments::Column(ref col), _)
-        | Count(FunctionArguments::Column(ref col), _)
         | Count(
             FunctionArguments::Conditional(CaseWhenExpression {
                 then_expr: ColumnOrLiteral::Column(ref col),
@@ -34,7 +38,7 @@ fn target_columns_from_computed_column(computed_col: &nom_sql::Column) -> Column
         )
         | Sum(FunctionArguments::Column(ref col), _) => Column::from(col),
         CountStar => {
-            // see comment re COUNT(*) rewriting in make_aggregation_node
+            // see comment re COUNT(*) rewriting in make_function_node
             panic!("COUNT(*) should have been rewritten earlier!")
         }
         _ => unreachable!(),
@@ -7,6 +7,7 @@ use petgraph::graph::NodeIndex;
 use dataflow::ops::filter::FilterCondition;
 use dataflow::ops::join::JoinType;
 
+use crate::controller::sql::passes::count_star_rewrite::count_star_column;
 use crate::controller::sql::query_graph::{OutputColumn, QueryGraph};
 use crate::controller::sql::query_signature::Signature;
 use nom_sql::{
@@ -59,6 +60,15 @@ fn predicate_columns(ce: &ConditionExpression) -> HashSet<Column> {
     cols
 }
 
+/// Combines a vector of conditions that must all hold into a single condition.
+fn conjunction(mut conds: Vec<(usize, FilterCondition)>) -> (usize, FilterCondition) {
+    if conds.len() == 1 {
+        conds.pop().unwrap()
+    } else {
+        (conds[0].0, FilterCondition::And(conds))
+    }
+}
+
 fn value_columns_needed_for_predicates(
     value_columns: &[OutputColumn],
     predicates: &[ConditionExpression],
@@ -163,35 +173,45 @@ impl SqlToMirConverter {
         }
     }
 
+    /// Converts an arbitrary condition expression into a vector of conditions that must all hold.
+    fn condition_expr_to_conditions(
+        &self,
+        ce: &ConditionExpression,
+        columns: &mut Vec<Column>,
+        n: &MirNodeRef,
+    ) -> Vec<(usize, FilterCondition)> {
+        match *ce {
+            ConditionExpression::LogicalOp(ref ct) => self.logical_op_to_conditions(ct, columns, n),
+            ConditionExpression::ComparisonOp(ref ct) => self.to_conditions(ct, columns, n),
+            ConditionExpression::Bracketed(ref inner) => {
+                self.condition_expr_to_conditions(inner, columns, n)
+            }
+            ConditionExpression::NegationOp(ref inner) => {
+                let (i, cond) = conjunction(self.condition_expr_to_conditions(inner, columns, n));
+                vec![(i, FilterCondition::Not(Box::new(cond)))]
+            }
+            _ => unimplemented!(),
+        }
+    }
+
     fn logical_op_to_conditions(
         &self,
         ct: &ConditionTree,
         columns: &mut Vec<Column>,
         n: &MirNodeRef,
     ) -> Vec<(usize, FilterCondition)> {
+        let mut left_filter = self.condition_expr_to_conditions(&ct.left, columns, n);
+        let mut right_filter = self.condition_expr_to_conditions(&ct.right, columns, n);
         match ct.operator {
             Operator::And => {
-                let mut left_filter = match ct.left.as_ref() {
-                    ConditionExpression::LogicalOp(ref ct2) => {
-                        self.logical_op_to_conditions(ct2, columns, n)
-                    }
-                    ConditionExpression::ComparisonOp(ref ct2) => {
-                        self.to_conditions(ct2, columns, n)
-                    }
-                    _ => unimplemented!(),
-                };
-                let mut right_filter = match ct.right.as_ref() {
-                    ConditionExpression::LogicalOp(ref ct2) => {
-                        self.logical_op_to_conditions(ct2, columns, n)
-                    }
-                    ConditionExpression::ComparisonOp(ref ct2) => {
-                        self.to_conditions(ct2, columns, n)
-                    }
-                    _ => unimplemented!(),
-                };
                 left_filter.append(&mut right_filter);
                 left_filter
             }
+            Operator::Or => {
+                let left = conjunction(left_filter);
+                let right = conjunction(right_filter);
+                vec![(left.0, FilterCondition::Or(vec![left, right]))]
+            }
             _ => unimplemented!(),
         }
     }
@@ -1067,22 +1087,29 @@ impl SqlToMirConverter {
                 false,
                 Some(condition),
             ),
-            Count(FunctionArguments::Column(ref col), distinct) => mknode(
-                &Column::from(col),
-                None,
-                GroupedNodeType::Aggregation(Aggregation::COUNT),
-                distinct,
-                None,
-            ),
-            CountStar => {
-                // XXX(malte): there is no "over" column, but our aggregation operators' API
-                // requires one to be specified, so we earlier rewrote it to use the last parent
-                // column (see passes/count_star_rewrite.rs). However, this isn't *entirely*
-                // faithful to COUNT(*) semantics, because COUNT(*) is supposed to count all
-                // rows including those with NULL values, and we don't have a mechanism to do that
-                // (but we also don't have a NULL value, so maybe we're okay).
-                panic!("COUNT(*) should have been rewritten earlier!")
+            Count(FunctionArguments::Column(ref col), distinct) => {
+                // there is no "over" column for COUNT(*), but our aggregation operators' API
+                // requires one to be specified, so we earlier rewrote it to use some parent column
+                // (see passes/count_star_rewrite.rs). Rows where that column is NULL must still
+                // be counted though, so it gets an aggregation that ignores the column's value.
+                match count_star_column(col) {
+                    Some(ref col) => mknode(
+                        &Column::from(col),
+                        None,
+                        GroupedNodeType::Aggregation(Aggregation::COUNT_STAR),
+                        distinct,
+                        None,
+                    ),
+                    None => mknode(
+                        &Column::from(col),
+                        None,
+                        GroupedNodeType::Aggregation(Aggregation::COUNT),
+                        distinct,
+                        None,
+                    ),
+                }
             }
+            CountStar => panic!("COUNT(*) should have been rewritten earlier!"),
             Count(
                 FunctionArguments::Conditional(CaseWhenExpression {
                     ref condition,
@@ -1191,12 +1218,9 @@ impl SqlToMirConverter {
                 let cond = condition.expect("Filter aggregation must have condition!");
                 let mut fields = parent_node.borrow().columns().to_vec();
                 let filter = match *cond {
-                    LogicalOp(ref ct) => {
-                        self.logical_op_to_conditions(ct, &mut fields, &parent_node)
+                    LogicalOp(_) | ComparisonOp(_) | Bracketed(_) | NegationOp(_) => {
+                        self.condition_expr_to_conditions(cond, &mut fields, &parent_node)
                     }
-                    ComparisonOp(ref ct) => self.to_conditions(ct, &mut fields, &parent_node),
-                    Bracketed(_) => unimplemented!(),
-                    NegationOp(_) => unreachable!("negation should have been removed earlier"),
                     Base(_) => unreachable!("dangling base predicate"),
                     Arithmetic(_) => unimplemented!(),
                 };
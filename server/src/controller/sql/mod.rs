@@ -19,7 +19,7 @@ use ::mir::Column;
 use ::mir::MirNodeRef;
 use dataflow::prelude::DataType;
 use nom_sql::parser as sql_parser;
-use nom_sql::{ArithmeticBase, CreateTableStatement, SqlQuery};
+use nom_sql::{ArithmeticBase, CreateTableStatement, SqlQuery, SqlType};
 use nom_sql::{CompoundSelectOperator, CompoundSelectStatement, SelectStatement};
 use petgraph::graph::NodeIndex;
 
@@ -853,13 +853,107 @@ impl SqlIncorporator {
 
         // Run some standard rewrite passes on the query. This makes the later work easier,
         // as we no longer have to consider complications like aliases.
-        Ok(fq
+        let fq = fq
             .expand_table_aliases(mig.context())
             .remove_negation()
             .coalesce_key_definitions()
             .expand_stars(&self.view_schemas)
             .expand_implied_tables(&self.view_schemas)
-            .rewrite_count_star(&self.view_schemas))
+            .rewrite_count_star(&self.view_schemas);
+
+        // negations have been removed by now, so `NOT x = ?` is also caught here
+        if let SqlQuery::Select(SelectStatement {
+            where_clause: Some(ref cond),
+            ..
+        }) = fq
+        {
+            query_graph::check_parameter_operators(cond)?;
+        }
+        if let SqlQuery::Select(ref st) = fq {
+            self.check_column_types(st)?;
+        }
+
+        Ok(fq)
+    }
+
+    /// Looks up the declared type of a column that belongs to a base table.
+    ///
+    /// Columns of views (and computed columns) have no recorded type, so `None` is returned.
+    fn base_column_type(&self, c: &nom_sql::Column) -> Option<&SqlType> {
+        let table = c.table.as_ref()?;
+        self.base_schemas.get(table).and_then(|ctq| {
+            ctq.fields
+                .iter()
+                .find(|cs| cs.column.name == c.name)
+                .map(|cs| &cs.sql_type)
+        })
+    }
+
+    /// Rejects queries that aggregate over text columns or compare text columns with numbers.
+    ///
+    /// Without this check, such queries install fine but then fail (or silently misbehave) once
+    /// records reach the operators at runtime.
+    fn check_column_types(&self, st: &SelectStatement) -> Result<(), String> {
+        use nom_sql::{FieldDefinitionExpression, FunctionArguments, FunctionExpression};
+
+        for field in &st.fields {
+            if let FieldDefinitionExpression::Col(ref c) = *field {
+                let (f, arg) = match c.function.as_ref().map(|f| &**f) {
+                    Some(FunctionExpression::Sum(FunctionArguments::Column(ref arg), _)) => {
+                        ("SUM", arg)
+                    }
+                    Some(FunctionExpression::Avg(FunctionArguments::Column(ref arg), _)) => {
+                        ("AVG", arg)
+                    }
+                    _ => continue,
+                };
+                if self.base_column_type(arg).map_or(false, is_text_type) {
+                    return Err(format!("cannot compute {} over text column {}", f, arg));
+                }
+            }
+        }
+
+        match st.where_clause {
+            Some(ref cond) => self.check_condition_types(cond),
+            None => Ok(()),
+        }
+    }
+
+    fn check_condition_types(&self, ce: &nom_sql::ConditionExpression) -> Result<(), String> {
+        use nom_sql::{ConditionBase, ConditionExpression, Literal};
+
+        match *ce {
+            ConditionExpression::LogicalOp(ref ct) => {
+                self.check_condition_types(&ct.left)?;
+                self.check_condition_types(&ct.right)
+            }
+            ConditionExpression::ComparisonOp(ref ct) => match (&*ct.left, &*ct.right) {
+                (
+                    ConditionExpression::Base(ConditionBase::Field(ref c)),
+                    ConditionExpression::Base(ConditionBase::Literal(Literal::Integer(_))),
+                )
+                | (
+                    ConditionExpression::Base(ConditionBase::Field(ref c)),
+                    ConditionExpression::Base(ConditionBase::Literal(Literal::FixedPoint(_))),
+                ) if self.base_column_type(c).map_or(false, is_text_type) => {
+                    Err(format!("cannot compare text column {} with a number", c))
+                }
+                (
+                    ConditionExpression::Base(ConditionBase::Field(ref l)),
+                    ConditionExpression::Base(ConditionBase::Field(ref r)),
+                ) => match (self.base_column_type(l), self.base_column_type(r)) {
+                    (Some(lt), Some(rt)) if is_text_type(lt) != is_text_type(rt) => Err(format!(
+                        "cannot compare column {} of type {} with column {} of type {}",
+                        l, lt, r, rt
+                    )),
+                    _ => Ok(()),
+                },
+                _ => Ok(()),
+            },
+            ConditionExpression::Bracketed(ref inner)
+            | ConditionExpression::NegationOp(ref inner) => self.check_condition_types(inner),
+            _ => Ok(()),
+        }
     }
 
     fn nodes_for_named_query(
@@ -969,6 +1063,18 @@ impl<'a> ToFlowParts for &'a str {
     }
 }
 
+fn is_text_type(t: &SqlType) -> bool {
+    match *t {
+        SqlType::Char(_)
+        | SqlType::Varchar(_)
+        | SqlType::Text
+        | SqlType::Tinytext
+        | SqlType::Mediumtext
+        | SqlType::Longtext => true,
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{SqlIncorporator, ToFlowParts};
@@ -1266,6 +1372,29 @@ mod tests {
         .await;
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn it_rejects_non_equality_parameters() {
+        // set up graph
+        let mut g = integration::start_simple("it_rejects_non_equality_parameters").await;
+        g.migrate(|mig| {
+            let mut inc = SqlIncorporator::default();
+            assert!(inc
+                .add_query("CREATE TABLE users (id int, age int);", None, mig)
+                .is_ok());
+
+            // readers can only look up parameters by equality
+            let res = inc.add_query("SELECT id FROM users WHERE users.age > ?;", None, mig);
+            assert!(res.is_err());
+            let res = inc.add_query("SELECT id FROM users WHERE ? < users.age;", None, mig);
+            assert!(res.is_err());
+            let res = inc.add_query("SELECT id FROM users WHERE NOT users.age = ?;", None, mig);
+            assert!(res.is_err());
+            let res = inc.add_query("SELECT id FROM users WHERE users.age = ?;", None, mig);
+            assert!(res.is_ok());
+        })
+        .await;
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn it_reuses_identical_query() {
         // set up graph
@@ -1513,7 +1642,10 @@ mod tests {
             assert_eq!(mig.graph().node_count(), 5);
             // check aggregation view
             let f = Box::new(FunctionExpression::Count(
-                FunctionArguments::Column(Column::from("votes.aid")),
+                FunctionArguments::Column(Column {
+                    alias: Some(super::passes::count_star_rewrite::COUNT_STAR_ALIAS.into()),
+                    ..Column::from("votes.aid")
+                }),
                 false,
             ));
             let qid = query_id_hash(
@@ -2258,6 +2390,56 @@ mod tests {
         .await;
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn it_rejects_mistyped_queries() {
+        let mut g = integration::start_simple("it_rejects_mistyped_queries").await;
+        g.migrate(|mig| {
+            let mut inc = SqlIncorporator::default();
+            assert!(inc
+                .add_query(
+                    "CREATE TABLE users (id int, name varchar(40), age int);",
+                    None,
+                    mig
+                )
+                .is_ok());
+            let ncount = mig.graph().node_count();
+
+            // aggregating over a text column is an error
+            assert!(inc
+                .add_query("SELECT SUM(users.name) FROM users;", None, mig)
+                .is_err());
+            assert!(inc
+                .add_query("SELECT AVG(name) FROM users;", None, mig)
+                .is_err());
+            // so is comparing text with numbers
+            assert!(inc
+                .add_query("SELECT id FROM users WHERE users.name = 42;", None, mig)
+                .is_err());
+            assert!(inc
+                .add_query(
+                    "SELECT id FROM users WHERE users.name = users.age;",
+                    None,
+                    mig
+                )
+                .is_err());
+            // none of the rejected queries should have added any nodes
+            assert_eq!(mig.graph().node_count(), ncount);
+
+            // well-typed queries are still fine
+            assert!(inc
+                .add_query(
+                    "SELECT SUM(users.age) FROM users GROUP BY users.id;",
+                    None,
+                    mig
+                )
+                .is_ok());
+            assert!(inc
+                .add_query("SELECT id FROM users WHERE users.name = 'bob';", None, mig)
+                .is_ok());
+        })
+        .await;
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn it_distinguishes_predicates() {
         // set up graph
@@ -5,6 +5,23 @@ use nom_sql::{
 
 use std::collections::HashMap;
 
+/// The alias given to the column the rewrite picks to stand in for `*` in `COUNT(*)`.
+///
+/// Unlike `COUNT(col)`, `COUNT(*)` also counts rows where that column is NULL, so the column is
+/// marked for later stages to tell the two apart.
+pub const COUNT_STAR_ALIAS: &str = "*";
+
+/// Returns the column standing in for `*` if `col` is the argument of a rewritten `COUNT(*)`.
+pub fn count_star_column(col: &Column) -> Option<Column> {
+    match col.alias {
+        Some(ref a) if a == COUNT_STAR_ALIAS => Some(Column {
+            alias: None,
+            ..col.clone()
+        }),
+        _ => None,
+    }
+}
+
 pub trait CountStarRewrite {
     fn rewrite_count_star(self, write_schemas: &HashMap<String, Vec<String>>) -> SqlQuery;
 }
@@ -65,7 +82,7 @@ impl CountStarRewrite for SqlQuery {
                     c.function = Some(Box::new(Count(
                         FunctionArguments::Column(Column {
                             name: bogo_column.clone(),
-                            alias: None,
+                            alias: Some(COUNT_STAR_ALIAS.into()),
                             table: Some(bogo_table.name.clone()),
                             function: None,
                         }),
@@ -107,7 +124,7 @@ impl CountStarRewrite for SqlQuery {
 
 #[cfg(test)]
 mod tests {
-    use super::CountStarRewrite;
+    use super::{CountStarRewrite, COUNT_STAR_ALIAS};
     use nom_sql::{Column, FieldDefinitionExpression, SqlQuery};
     use std::collections::HashMap;
 
@@ -136,7 +153,10 @@ mod tests {
                         alias: None,
                         table: None,
                         function: Some(Box::new(FunctionExpression::Count(
-                            FunctionArguments::Column(Column::from("users.id")),
+                            FunctionArguments::Column(Column {
+                                alias: Some(COUNT_STAR_ALIAS.into()),
+                                ..Column::from("users.id")
+                            }),
                             false,
                         ))),
                     })]
@@ -172,7 +192,10 @@ mod tests {
                         alias: None,
                         table: None,
                         function: Some(Box::new(FunctionExpression::Count(
-                            FunctionArguments::Column(Column::from("users.name")),
+                            FunctionArguments::Column(Column {
+                                alias: Some(COUNT_STAR_ALIAS.into()),
+                                ..Column::from("users.name")
+                            }),
                             false,
                         ))),
                     })]
@@ -455,6 +455,37 @@ fn classify_conditionals(
     }
 }
 
+/// Query parameters are looked up by equality in the leaf view's reader, so a parameter that is
+/// compared using any other operator would silently be treated as an equality lookup.
+pub(super) fn check_parameter_operators(ce: &ConditionExpression) -> Result<(), String> {
+    match *ce {
+        ConditionExpression::LogicalOp(ref ct) => {
+            check_parameter_operators(&ct.left)?;
+            check_parameter_operators(&ct.right)
+        }
+        ConditionExpression::ComparisonOp(ref ct) => {
+            let is_parameter = |ce: &ConditionExpression| match *ce {
+                ConditionExpression::Base(ConditionBase::Literal(Literal::Placeholder)) => true,
+                _ => false,
+            };
+            // the parameter may be on either side of the comparison
+            if ct.operator != Operator::Equal && (is_parameter(&ct.left) || is_parameter(&ct.right))
+            {
+                Err(format!(
+                    "query parameters can only be compared using =, not {}",
+                    ct.operator
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        ConditionExpression::Bracketed(ref inner) | ConditionExpression::NegationOp(ref inner) => {
+            check_parameter_operators(inner)
+        }
+        _ => Ok(()),
+    }
+}
+
 #[allow(clippy::cognitive_complexity)]
 pub fn to_query_graph(st: &SelectStatement) -> Result<QueryGraph, String> {
     let mut qg = QueryGraph::new();
@@ -10,9 +10,10 @@ use dataflow::ops::project::Project;
 use dataflow::ops::union::Union;
 use dataflow::{DurabilityMode, PersistenceParameters};
 use noria::consensus::LocalAuthority;
-use noria::DataType;
+use noria::{DataType, ReadLimit};
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{env, thread};
@@ -209,6 +210,73 @@ async fn it_completes() {
     done.await;
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn it_pushes_back_on_writers_through_slow_domains() {
+    const HIGH_WATER: usize = 4;
+    const FANOUT: usize = 200;
+    const BATCH: usize = 4;
+    const BATCHES: usize = 500;
+
+    let mut builder = Builder::default();
+    builder.set_sharding(None);
+    builder.disable_partial();
+    builder.set_outbox_high_water(HIGH_WATER);
+    builder.set_persistence(get_persistence_params(
+        "it_pushes_back_on_writers_through_slow_domains",
+    ));
+    let mut g = builder.start_local().await.unwrap().0;
+
+    // every item joins with FANOUT tags, so the query's domain does far more work per write than
+    // the base table's domain, which only forwards it
+    let sql = "
+        CREATE TABLE Item (id int, kind int, tag int, PRIMARY KEY(id));
+        CREATE TABLE Tag (id int, tag int, PRIMARY KEY(id));
+        QUERY TagsByKind: SELECT COUNT(Tag.id) FROM Item \
+                  JOIN Tag ON (Item.tag = Tag.tag) WHERE Item.kind = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut items = g.table("Item").await.unwrap();
+    let mut tags = g.table("Tag").await.unwrap();
+    let mut getter = g.view("TagsByKind").await.unwrap();
+
+    tags.insert_many((0..FANOUT).map(|i| vec![(i as i32).into(), 1.into()]))
+        .await
+        .unwrap();
+    sleep().await;
+
+    let count = |rs: Vec<Vec<DataType>>| match rs.first() {
+        Some(r) => i64::from(&r[0]) as usize,
+        None => 0,
+    };
+
+    for b in 0..BATCHES {
+        items
+            .insert_many((0..BATCH).map(|i| {
+                let id = (b * BATCH + i) as i32;
+                vec![id.into(), 1.into(), 1.into()]
+            }))
+            .await
+            .unwrap();
+    }
+
+    // every write has been acknowledged, so the slow domain can only be as far behind as the
+    // outboxes and channels between it and the writer allow
+    let seen = count(getter.lookup(&[1.into()], false).await.unwrap());
+    let allowed = 8 * HIGH_WATER * BATCH * FANOUT;
+    let written = BATCHES * BATCH * FANOUT;
+    assert!(
+        seen + allowed >= written,
+        "query saw {} of {} rows after the last write was acknowledged",
+        seen,
+        written
+    );
+
+    sleep().await;
+    let seen = count(getter.lookup(&[1.into()], true).await.unwrap());
+    assert_eq!(seen, written);
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn sharded_shuffle() {
     let mut g = start_simple("sharded_shuffle").await;
@@ -521,6 +589,135 @@ async fn it_works_w_mat() {
     assert!(res.iter().any(|r| r == &vec![id.clone(), 6.into()]));
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn it_works_w_ordered_reader() {
+    let mut g = start_simple("it_works_w_ordered_reader").await;
+    let _ = g
+        .migrate(|mig| {
+            let a = mig.add_base("a", &["id", "a", "b"], Base::new(vec![]).with_key(vec![0]));
+            let b = mig.add_ingredient("b", &["id", "a", "b"], Identity::new(a));
+            mig.maintain_anonymous(b, &[1]);
+            mig.order_reader_by(b, 2);
+            (a, b)
+        })
+        .await;
+
+    let mut bq = g.view("b").await.unwrap();
+    let mut muta = g.table("a").await.unwrap();
+    let a: DataType = 1.into();
+
+    for (id, v) in [3, 1, 4, 5, 2].iter().enumerate() {
+        muta.insert(vec![id.into(), a.clone(), (*v).into()])
+            .await
+            .unwrap();
+    }
+    sleep().await;
+
+    let first = bq
+        .lookup_limited(&[a.clone()], ReadLimit::First(2), true)
+        .await
+        .unwrap();
+    assert_eq!(
+        first.iter().map(|r| r["b"].clone()).collect::<Vec<_>>(),
+        vec![1.into(), 2.into()]
+    );
+    let last = bq
+        .lookup_limited(&[a.clone()], ReadLimit::Last(2), true)
+        .await
+        .unwrap();
+    assert_eq!(
+        last.iter().map(|r| r["b"].clone()).collect::<Vec<_>>(),
+        vec![5.into(), 4.into()]
+    );
+
+    // removing a row keeps the remaining rows in order
+    muta.delete(vec![3.into()]).await.unwrap();
+    sleep().await;
+
+    let last = bq
+        .lookup_limited(&[a.clone()], ReadLimit::Last(1), true)
+        .await
+        .unwrap();
+    assert_eq!(
+        last.iter().map(|r| r["b"].clone()).collect::<Vec<_>>(),
+        vec![4.into()]
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_works_w_distance_filtered_reader() {
+    use dataflow::ops::filter::{FilterCondition, Operator, Value};
+
+    let mut g = start_simple("it_works_w_distance_filtered_reader").await;
+    let _ = g
+        .migrate(|mig| {
+            let a = mig.add_base(
+                "a",
+                &["id", "kind", "lat", "lon"],
+                Base::new(vec![]).with_key(vec![0]),
+            );
+            let b = mig.add_ingredient("b", &["id", "kind", "lat", "lon"], Identity::new(a));
+            mig.maintain_anonymous(b, &[1]);
+            mig.filter_reader_by(
+                b,
+                &[(
+                    2,
+                    FilterCondition::Distance {
+                        lon: 3,
+                        from: (Value::Argument(0), Value::Argument(1)),
+                        op: Operator::LessOrEqual,
+                        radius: 10.0,
+                    },
+                )],
+            );
+            (a, b)
+        })
+        .await;
+
+    let mut bq = g.view("b").await.unwrap();
+    let mut muta = g.table("a").await.unwrap();
+    let cafe: DataType = "cafe".into();
+
+    // one in Cambridge, MA, and one in New York
+    muta.insert(vec![
+        1.into(),
+        cafe.clone(),
+        42.3736.into(),
+        (-71.1097).into(),
+    ])
+    .await
+    .unwrap();
+    muta.insert(vec![
+        2.into(),
+        cafe.clone(),
+        40.7128.into(),
+        (-74.0060).into(),
+    ])
+    .await
+    .unwrap();
+    sleep().await;
+
+    let boston = [42.3601.into(), (-71.0589).into()];
+    let near = bq
+        .lookup_with_args(&[cafe.clone()], &boston, true)
+        .await
+        .unwrap();
+    assert_eq!(
+        near.iter().map(|r| r["id"].clone()).collect::<Vec<_>>(),
+        vec![1.into()]
+    );
+
+    let new_york = [40.7306.into(), (-73.9352).into()];
+    let near = bq
+        .lookup_with_args(&[cafe.clone()], &new_york, true)
+        .await
+        .unwrap();
+    assert_eq!(
+        near.iter().map(|r| r["id"].clone()).collect::<Vec<_>>(),
+        vec![2.into()]
+    );
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn it_works_w_partial_mat() {
     // set up graph
@@ -622,6 +819,60 @@ async fn it_works_w_partial_mat_below_empty() {
     assert_eq!(cq.len().await.unwrap(), 1);
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn it_keeps_partial_keys_fresh() {
+    // set up graph
+    let mut g = start_simple("it_keeps_partial_keys_fresh").await;
+    let _ = g
+        .migrate(|mig| {
+            let a = mig.add_base("a", &["a", "b"], Base::default());
+            let b = mig.add_base("b", &["a", "b"], Base::default());
+            let mut emits = HashMap::new();
+            emits.insert(a, vec![0, 1]);
+            emits.insert(b, vec![0, 1]);
+            let u = Union::new(emits);
+            let c = mig.add_ingredient("c", &["a", "b"], u);
+            mig.maintain_anonymous(c, &[0]);
+            (a, b, c)
+        })
+        .await;
+
+    let mut muta = g.table("a").await.unwrap();
+    let mut mutb = g.table("b").await.unwrap();
+    let id: DataType = 1.into();
+    let other: DataType = 2.into();
+
+    muta.insert(vec![id.clone(), 1.into()]).await.unwrap();
+    muta.insert(vec![other.clone(), 1.into()]).await.unwrap();
+    sleep().await;
+
+    let mut cq = g.view("c").await.unwrap();
+    assert_eq!(cq.len().await.unwrap(), 0);
+
+    // fill one key through a replay
+    let res = cq.lookup(&[id.clone()], true).await.unwrap();
+    assert_eq!(res, vec![vec![id.clone(), 1.into()]]);
+    assert_eq!(cq.len().await.unwrap(), 1);
+
+    // writes to the filled key should now be applied directly
+    mutb.insert(vec![id.clone(), 2.into()]).await.unwrap();
+    sleep().await;
+
+    let res = cq.lookup(&[id.clone()], false).await.unwrap();
+    assert_eq!(res.len(), 2);
+    assert!(res.iter().any(|r| r == &vec![id.clone(), 2.into()]));
+
+    // while writes to keys nobody has read are dropped at the reader
+    muta.insert(vec![other.clone(), 2.into()]).await.unwrap();
+    sleep().await;
+    assert_eq!(cq.len().await.unwrap(), 1);
+
+    // until they are read, at which point they reflect all writes so far
+    let res = cq.lookup(&[other.clone()], true).await.unwrap();
+    assert_eq!(res.len(), 2);
+    assert_eq!(cq.len().await.unwrap(), 2);
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn it_works_deletion() {
     // set up graph
@@ -673,6 +924,177 @@ async fn it_works_deletion() {
     );
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn it_works_with_batched_writes() {
+    // set up graph
+    let mut g = start_simple("it_works_with_batched_writes").await;
+    let _ = g
+        .migrate(|mig| {
+            let a = mig.add_base("a", &["x", "y"], Base::new(vec![]).with_key(vec![0]));
+            mig.maintain_anonymous(a, &[1]);
+            a
+        })
+        .await;
+
+    let mut aq = g.view("a").await.unwrap();
+    let mut muta = g.table("a").await.unwrap();
+
+    muta.insert_many((0..10).map(|i| vec![DataType::from(i), DataType::from(i % 2)]))
+        .await
+        .unwrap();
+    sleep().await;
+    assert_eq!(aq.lookup(&[0.into()], true).await.unwrap().len(), 5);
+    assert_eq!(aq.lookup(&[1.into()], true).await.unwrap().len(), 5);
+
+    // delete all the odd rows
+    muta.delete_many(
+        (0..10)
+            .filter(|i| i % 2 == 1)
+            .map(|i| vec![DataType::from(i)]),
+    )
+    .await
+    .unwrap();
+    sleep().await;
+    assert_eq!(aq.lookup(&[0.into()], true).await.unwrap().len(), 5);
+    assert!(aq.lookup(&[1.into()], true).await.unwrap().is_empty());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_loads_delimited_text() {
+    let mut g = start_simple("it_loads_delimited_text").await;
+    let sql = "
+        CREATE TABLE Car (id int, brand varchar(255), price double, PRIMARY KEY(id));
+        QUERY CarsByBrand: SELECT id, price FROM Car WHERE brand = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Car").await.unwrap();
+    let mut getter = g.view("CarsByBrand").await.unwrap();
+
+    let input = "1,Volvo,30000.5\n\
+                 2,\"Volvo\",\n\
+                 three,Volvo,1.0\n\
+                 4,\"Mercedes, \"\"Benz\"\"\",70000\n\
+                 5,Volvo\n";
+    let mut batches = Vec::new();
+    let summary = mutator
+        .load_delimited(input.as_bytes(), ',', 2, |n| batches.push(n))
+        .await
+        .unwrap();
+    assert_eq!(summary.rows, 3);
+    assert_eq!(batches, vec![2, 3]);
+    assert_eq!(
+        summary.errors.iter().map(|&(l, _)| l).collect::<Vec<_>>(),
+        vec![3, 5]
+    );
+    sleep().await;
+
+    let pairs = |rows: Vec<Vec<DataType>>| {
+        let mut rows: Vec<_> = rows.iter().map(|r| (r[0].clone(), r[1].clone())).collect();
+        rows.sort();
+        rows
+    };
+    let volvos = getter.lookup(&["Volvo".into()], true).await.unwrap();
+    assert_eq!(
+        pairs(volvos.into()),
+        vec![(1.into(), 30000.5.into()), (2.into(), DataType::None)]
+    );
+    let benz = getter
+        .lookup(&["Mercedes, \"Benz\"".into()], true)
+        .await
+        .unwrap();
+    assert_eq!(pairs(benz.into()), vec![(4.into(), 70000.0.into())]);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_stops_delimited_loads_on_write_errors() {
+    use noria::error::{LoadError, TableError};
+
+    let mut g = start_simple("it_stops_delimited_loads_on_write_errors").await;
+    let sql = "
+        CREATE TABLE Car (id int, brand varchar(255) NOT NULL, PRIMARY KEY(id));
+        QUERY CarById: SELECT brand FROM Car WHERE id = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Car").await.unwrap();
+    let mut getter = g.view("CarById").await.unwrap();
+
+    match mutator
+        .load_delimited(&b"1,Volvo\n"[..], ',', 0, |_| ())
+        .await
+    {
+        Err(LoadError {
+            error: TableError::ZeroBatchSize,
+            ..
+        }) => {}
+        r => panic!("expected an empty batch size to be rejected, got {:?}", r),
+    }
+
+    // rows that don't fit the schema are skipped, but a failed write ends the load
+    let input = "1,Volvo\n2,\n1,Saab\n3,Audi\n";
+    let mut batches = Vec::new();
+    match mutator
+        .load_delimited(input.as_bytes(), ',', 1, |n| batches.push(n))
+        .await
+    {
+        Err(LoadError {
+            summary,
+            error: TableError::DuplicateKey(keys),
+        }) => {
+            assert_eq!(keys, vec![vec![DataType::from(1)]]);
+            assert_eq!(summary.rows, 1);
+            assert_eq!(
+                summary.errors.iter().map(|&(l, _)| l).collect::<Vec<_>>(),
+                vec![2]
+            );
+        }
+        r => panic!("expected duplicate key to stop the load, got {:?}", r),
+    }
+    assert_eq!(batches, vec![1]);
+    sleep().await;
+
+    let result = getter.lookup(&[1.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], "Volvo".into());
+    assert!(getter.lookup(&[2.into()], true).await.unwrap().is_empty());
+    assert!(getter.lookup(&[3.into()], true).await.unwrap().is_empty());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_loads_delimited_decimals() {
+    let mut g = start_simple("it_loads_delimited_decimals").await;
+    let sql = "
+        CREATE TABLE Item (id int, price decimal(10, 2), PRIMARY KEY(id));
+        QUERY ItemById: SELECT id, price FROM Item WHERE id = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Item").await.unwrap();
+    let mut getter = g.view("ItemById").await.unwrap();
+
+    let input = "1,19.5\n2,0.125\n3,0.1\n4,cheap\n";
+    let summary = mutator
+        .load_delimited(input.as_bytes(), ',', 10, |_| ())
+        .await
+        .unwrap();
+    assert_eq!(summary.rows, 3);
+    assert_eq!(
+        summary.errors.iter().map(|&(l, _)| l).collect::<Vec<_>>(),
+        vec![4]
+    );
+    sleep().await;
+
+    // prices are exact, and kept at the column's scale
+    for &(id, cents) in &[(1, 1950), (2, 13), (3, 10)] {
+        let rows = getter.lookup(&[id.into()], true).await.unwrap();
+        assert_eq!(
+            Vec::<Vec<DataType>>::from(rows),
+            vec![vec![id.into(), DataType::Decimal(cents, 2)]]
+        );
+    }
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn it_works_with_sql_recipe() {
     let mut g = start_simple("it_works_with_sql_recipe").await;
@@ -702,6 +1124,389 @@ async fn it_works_with_sql_recipe() {
     assert_eq!(result[0][0], 2.into());
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn it_counts_nulls_with_count_star() {
+    let mut g = start_simple("it_counts_nulls_with_count_star").await;
+    // COUNT(*) is computed over `model`, the first column that isn't also a parameter
+    let sql = "
+        CREATE TABLE Car (model varchar(255), brand varchar(255));
+        QUERY CountCars: SELECT COUNT(*) FROM Car WHERE brand = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Car").await.unwrap();
+    let mut getter = g.view("CountCars").await.unwrap();
+
+    mutator
+        .insert(vec!["V70".into(), "Volvo".into()])
+        .await
+        .unwrap();
+    mutator
+        .insert(vec![DataType::None, "Volvo".into()])
+        .await
+        .unwrap();
+
+    sleep().await;
+
+    let result = getter.lookup(&["Volvo".into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], 2.into());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_parses_uuids_on_insert() {
+    let mut g = start_simple("it_parses_uuids_on_insert").await;
+    let sql = "
+        CREATE TABLE Device (id binary(16), name varchar(255), PRIMARY KEY(id));
+        QUERY DeviceById: SELECT name FROM Device WHERE id = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Device").await.unwrap();
+    let mut getter = g.view("DeviceById").await.unwrap();
+
+    let id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    mutator
+        .insert(vec![id.into(), "sensor".into()])
+        .await
+        .unwrap();
+
+    sleep().await;
+
+    let key = DataType::parse_uuid(id).unwrap();
+    let result = getter.lookup(&[key], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], "sensor".into());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_enforces_column_constraints() {
+    use noria::error::TableError;
+
+    let mut g = start_simple("it_enforces_column_constraints").await;
+    let sql = "
+        CREATE TABLE Car (id int, brand varchar(255) NOT NULL, seats int, PRIMARY KEY(id));
+        QUERY CarById: SELECT brand FROM Car WHERE id = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Car").await.unwrap();
+    let mut getter = g.view("CarById").await.unwrap();
+
+    match mutator
+        .insert(vec![1.into(), DataType::None, 4.into()])
+        .await
+    {
+        Err(TableError::NullValue(ref column)) => assert_eq!(column, "brand"),
+        r => panic!("expected NULL to be rejected, got {:?}", r),
+    }
+    match mutator
+        .insert(vec![1.into(), "Volvo".into(), "four".into()])
+        .await
+    {
+        Err(TableError::WrongType(ref column, ..)) => assert_eq!(column, "seats"),
+        r => panic!("expected text to be rejected, got {:?}", r),
+    }
+
+    mutator
+        .insert(vec![1.into(), "Volvo".into(), 4.into()])
+        .await
+        .unwrap();
+    match mutator
+        .insert_many(vec![
+            vec![1.into(), "Saab".into(), 2.into()],
+            vec![2.into(), "Saab".into(), 2.into()],
+        ])
+        .await
+    {
+        Err(TableError::DuplicateKey(keys)) => assert_eq!(keys, vec![vec![DataType::from(1)]]),
+        r => panic!("expected duplicate key to be rejected, got {:?}", r),
+    }
+
+    sleep().await;
+
+    // the rejected insert left the existing row alone, but the rest of the batch went through
+    let result = getter.lookup(&[1.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], "Volvo".into());
+    let result = getter.lookup(&[2.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], "Saab".into());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_accepts_decimals_and_floats_in_numeric_columns() {
+    let mut g = start_simple("it_accepts_decimals_and_floats_in_numeric_columns").await;
+    let sql = "
+        CREATE TABLE Item (id int, price decimal(10, 2), weight double, PRIMARY KEY(id));
+        QUERY ItemById: SELECT price, weight FROM Item WHERE id = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Item").await.unwrap();
+    let mut getter = g.view("ItemById").await.unwrap();
+
+    let price = DataType::parse_decimal("12.50").unwrap();
+    let weight = DataType::Float(0.75);
+    mutator
+        .insert(vec![1.into(), price.clone(), weight.clone()])
+        .await
+        .unwrap();
+    // and the other way around, since both columns are numeric
+    mutator
+        .insert(vec![2.into(), DataType::Float(3.5), price.clone()])
+        .await
+        .unwrap();
+
+    sleep().await;
+
+    let result = getter.lookup(&[1.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], price);
+    assert_eq!(result[0][1], weight);
+    let result = getter.lookup(&[2.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], DataType::Float(3.5));
+    assert_eq!(result[0][1], price);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_assigns_ids_and_defaults() {
+    let mut g = start_simple("it_assigns_ids_and_defaults").await;
+    let sql = "
+        CREATE TABLE Post (id int AUTO_INCREMENT, title text, status varchar(10) NOT NULL DEFAULT 'draft', PRIMARY KEY(id));
+        QUERY PostById: SELECT title, status FROM Post WHERE id = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Post").await.unwrap();
+    let mut getter = g.view("PostById").await.unwrap();
+
+    let id = mutator
+        .insert_returning_id(vec![DataType::None, "first".into(), DataType::None])
+        .await
+        .unwrap();
+    assert_eq!(id, Some(1.into()));
+    let id = mutator
+        .insert_returning_id(vec![DataType::None, "second".into(), "published".into()])
+        .await
+        .unwrap();
+    assert_eq!(id, Some(2.into()));
+
+    // ids that the writer picks are not reported back, but are never handed out again
+    let id = mutator
+        .insert_returning_id(vec![10.into(), "third".into(), DataType::None])
+        .await
+        .unwrap();
+    assert_eq!(id, None);
+    let id = mutator
+        .insert_returning_id(vec![DataType::None, "fourth".into(), DataType::None])
+        .await
+        .unwrap();
+    assert_eq!(id, Some(11.into()));
+
+    sleep().await;
+
+    let result = getter.lookup(&[1.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], "first".into());
+    assert_eq!(result[0][1], "draft".into());
+    let result = getter.lookup(&[2.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][1], "published".into());
+    let result = getter.lookup(&[11.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][0], "fourth".into());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_serves_rest_reads_and_writes() {
+    use noria::consensus::Authority;
+    use noria::ControllerDescriptor;
+
+    let authority = Arc::new(LocalAuthority::new());
+    let mut builder = Builder::default();
+    builder.set_persistence(get_persistence_params("it_serves_rest_reads_and_writes"));
+    let (mut g, _) = builder.start(authority.clone()).await.unwrap();
+    g.install_recipe(
+        "CREATE TABLE Article (id int, title varchar(255), PRIMARY KEY(id));
+         QUERY ArticleById: SELECT id, title FROM Article WHERE id = ?;
+         QUERY ArticleByTitle: SELECT id, title FROM Article WHERE title = ?;",
+    )
+    .await
+    .unwrap();
+
+    let descriptor: ControllerDescriptor =
+        serde_json::from_slice(&authority.get_leader().unwrap().1).unwrap();
+    let url = format!("http://{}", descriptor.external_addr);
+    let client = hyper::Client::new();
+
+    // every endpoint is hit more than once, so that the later requests use the cached handles
+    for id in 1..=2 {
+        let req = hyper::Request::post(format!("{}/table/Article", url))
+            .body(hyper::Body::from(format!("[[{}, \"title {}\"]]", id, id)))
+            .unwrap();
+        let res = client.request(req).await.unwrap();
+        assert_eq!(res.status(), hyper::StatusCode::OK);
+    }
+    sleep().await;
+
+    for id in 1..=2 {
+        let uri = format!("{}/view/ArticleById/{}", url, id).parse().unwrap();
+        let res = client.get(uri).await.unwrap();
+        assert_eq!(res.status(), hyper::StatusCode::OK);
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        let rows: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(rows, serde_json::json!([[id, format!("title {}", id)]]));
+    }
+
+    // keys are percent-decoded, and parsed as their column's type rather than as whatever they
+    // look like
+    let req = hyper::Request::post(format!("{}/table/Article", url))
+        .body(hyper::Body::from("[[3, \"007\"], [4, \"a/b\"]]"))
+        .unwrap();
+    let res = client.request(req).await.unwrap();
+    assert_eq!(res.status(), hyper::StatusCode::OK);
+    sleep().await;
+    for (path, expected) in &[
+        (
+            "ArticleByTitle/title%201",
+            serde_json::json!([[1, "title 1"]]),
+        ),
+        ("ArticleByTitle/007", serde_json::json!([[3, "007"]])),
+        ("ArticleByTitle/a%2Fb", serde_json::json!([[4, "a/b"]])),
+        ("ArticleById/%33", serde_json::json!([[3, "007"]])),
+    ] {
+        let uri = format!("{}/view/{}", url, path).parse().unwrap();
+        let res = client.get(uri).await.unwrap();
+        assert_eq!(res.status(), hyper::StatusCode::OK);
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        let rows: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(&rows, expected, "{}", path);
+    }
+    for path in &["ArticleById/three", "ArticleById/1/2", "ArticleById/%FF"] {
+        let uri = format!("{}/view/{}", url, path).parse().unwrap();
+        let res = client.get(uri).await.unwrap();
+        assert_eq!(res.status(), hyper::StatusCode::BAD_REQUEST, "{}", path);
+    }
+
+    let uri = format!("{}/view/NoSuchView/1", url).parse().unwrap();
+    let res = client.get(uri).await.unwrap();
+    assert_eq!(res.status(), hyper::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_applies_same_key_writes_in_order() {
+    let mut g = start_simple("it_applies_same_key_writes_in_order").await;
+    let sql = "
+        CREATE TABLE counter (id int, value int, PRIMARY KEY(id));
+        QUERY CounterByValue: SELECT id FROM counter WHERE value = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("counter").await.unwrap();
+    let mut getter = g.view("CounterByValue").await.unwrap();
+
+    // the view is keyed on a different column than the base, so every write to a counter moves
+    // it between shards of the view. if any two writes were applied out of order, the counter
+    // would end up at the wrong value, or in two places at once.
+    for id in 0..4i32 {
+        for v in 0..50i32 {
+            mutator
+                .insert_or_update(
+                    vec![id.into(), v.into()],
+                    vec![(1, noria::Modification::Set(v.into()))],
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    sleep().await;
+
+    let result = getter.lookup(&[49.into()], true).await.unwrap();
+    let mut ids: Vec<DataType> = result.into_iter().map(|r| r[0].clone()).collect();
+    ids.sort();
+    assert_eq!(ids, (0..4i32).map(DataType::from).collect::<Vec<_>>());
+    for v in 0..49i32 {
+        assert!(getter.lookup(&[v.into()], true).await.unwrap().is_empty());
+    }
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_works_with_logical_ops_in_case() {
+    let mut g = start_simple("it_works_with_logical_ops_in_case").await;
+    let sql = "
+        CREATE TABLE votes (userid int, aid int, sign int);
+        QUERY Votes: SELECT userid, SUM(CASE WHEN aid = 5 OR NOT (sign = 1) THEN sign END) AS score \
+                     FROM votes WHERE userid = ? GROUP BY userid;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("votes").await.unwrap();
+    let mut getter = g.view("Votes").await.unwrap();
+
+    for &(aid, sign) in &[(5, 1), (6, 1), (7, 3), (5, 2)] {
+        mutator
+            .insert(vec![1.into(), aid.into(), sign.into()])
+            .await
+            .unwrap();
+    }
+
+    sleep().await;
+
+    // aid = 5 contributes 1 + 2, and sign != 1 contributes 3
+    let result = getter.lookup(&[1.into()], true).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0][1], 6.into());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_serves_two_keys_from_one_table() {
+    let mut g = start_simple("it_serves_two_keys_from_one_table").await;
+    let sql = "
+        CREATE TABLE Post (id int, author int, score int);
+        QUERY PostById: SELECT id, author, score FROM Post WHERE id = ?;
+        QUERY PostsByAuthor: SELECT id, author, score FROM Post WHERE author = ?;
+    ";
+    g.install_recipe(sql).await.unwrap();
+
+    let mut mutator = g.table("Post").await.unwrap();
+    let mut by_id = g.view("PostById").await.unwrap();
+    let mut by_author = g.view("PostsByAuthor").await.unwrap();
+
+    for &(id, author) in &[(1, 10), (2, 10), (3, 20)] {
+        mutator
+            .insert(vec![id.into(), author.into(), 0.into()])
+            .await
+            .unwrap();
+    }
+    sleep().await;
+
+    // both readers are filled by replays from the base table's state, which needs an index on
+    // each of the two key columns
+    assert_eq!(by_id.len().await.unwrap(), 0);
+    assert_eq!(by_author.len().await.unwrap(), 0);
+
+    let result = by_id.lookup(&[2.into()], true).await.unwrap();
+    assert_eq!(result, vec![vec![2.into(), 10.into(), 0.into()]]);
+    let result = by_author.lookup(&[10.into()], true).await.unwrap();
+    assert_eq!(result.len(), 2);
+    assert!(result.iter().any(|r| r[0] == 1.into()));
+    assert!(result.iter().any(|r| r[0] == 2.into()));
+
+    mutator
+        .insert(vec![4.into(), 10.into(), 1.into()])
+        .await
+        .unwrap();
+    sleep().await;
+
+    let result = by_author.lookup(&[10.into()], true).await.unwrap();
+    assert_eq!(result.len(), 3);
+    let result = by_id.lookup(&[4.into()], true).await.unwrap();
+    assert_eq!(result, vec![vec![4.into(), 10.into(), 1.into()]]);
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn it_works_with_vote() {
     let mut g = start_simple("it_works_with_vote").await;
@@ -2155,6 +2960,142 @@ async fn independent_domain_migration() {
     );
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn it_works_with_explicit_placement() {
+    let id: DataType = 1.into();
+
+    // set up graph
+    let mut g = start_simple("it_works_with_explicit_placement").await;
+    let _ = g
+        .migrate(|mig| {
+            let a = mig.add_base("a", &["a", "b"], Base::default());
+            let b = mig.add_base("b", &["a", "c"], Base::default());
+            let j = Join::new(a, b, JoinType::Inner, vec![B(0, 0), L(1), R(1)]);
+            let j = mig.add_ingredient("j", &["a", "b", "c"], j);
+            let i = mig.add_ingredient("i", &["a", "b", "c"], Identity::new(j));
+            // split the join off from its parents, but keep the identity next to it
+            mig.place_in_new_domain(j);
+            mig.place_with(i, j);
+            mig.maintain_anonymous(i, &[0]);
+            i
+        })
+        .await;
+
+    let mut iq = g.view("i").await.unwrap();
+    let mut muta = g.table("a").await.unwrap();
+    let mut mutb = g.table("b").await.unwrap();
+
+    muta.insert(vec![id.clone(), 2.into()]).await.unwrap();
+    mutb.insert(vec![id.clone(), 3.into()]).await.unwrap();
+    sleep().await;
+
+    assert_eq!(
+        iq.lookup(&[id.clone()], true).await.unwrap(),
+        vec![vec![1.into(), 2.into(), 3.into()]]
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_explains_views() {
+    let mut g = start_simple("it_explains_views").await;
+    let _ = g
+        .migrate(|mig| {
+            let a = mig.add_base("a", &["a", "b"], Base::new(vec![]).with_key(vec![0]));
+            let b = mig.add_base("b", &["a", "c"], Base::new(vec![]).with_key(vec![0]));
+            let j = Join::new(a, b, JoinType::Inner, vec![B(0, 0), L(1), R(1)]);
+            let j = mig.add_ingredient("j", &["a", "b", "c"], j);
+            mig.maintain_anonymous(j, &[0]);
+            j
+        })
+        .await;
+
+    let plan = g.explain("j").await.unwrap();
+    assert_eq!(plan.name, "j");
+    assert_eq!(plan.indices, vec![vec![0]]);
+    let mut parents: Vec<_> = plan.parents.iter().map(|p| &p.name[..]).collect();
+    parents.sort();
+    assert_eq!(parents, vec!["a", "b"]);
+    assert!(plan.parents.iter().all(|p| p.parents.is_empty()));
+    assert!(plan.to_string().starts_with("j "));
+
+    assert!(g.explain("nope").await.is_err());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_reports_node_statistics() {
+    let mut g = start_simple("it_reports_node_statistics").await;
+    let (a, j) = g
+        .migrate(|mig| {
+            let a = mig.add_base("a", &["a", "b"], Base::new(vec![]).with_key(vec![0]));
+            let b = mig.add_base("b", &["a", "c"], Base::new(vec![]).with_key(vec![0]));
+            let j = Join::new(a, b, JoinType::Inner, vec![B(0, 0), L(1), R(1)]);
+            let j = mig.add_ingredient("j", &["a", "b", "c"], j);
+            mig.maintain_anonymous(j, &[0]);
+            (a, j)
+        })
+        .await;
+
+    let mut muta = g.table("a").await.unwrap();
+    let mut mutb = g.table("b").await.unwrap();
+    mutb.insert(vec![1.into(), 2.into()]).await.unwrap();
+    muta.insert_many(vec![vec![1.into(), 3.into()], vec![2.into(), 4.into()]])
+        .await
+        .unwrap();
+    sleep().await;
+
+    let stats = g.statistics().await.unwrap();
+    let nodes: HashMap<_, _> = stats.values().flat_map(|(_, nodes)| nodes.iter()).collect();
+    assert_eq!(nodes[&a].records, 2);
+    assert_eq!(nodes[&a].rows, 2);
+    // one probe for b's write, and one for each join key in a's
+    assert_eq!(nodes[&j].probe_result["probes"], "3");
+    assert_eq!(nodes[&j].probe_result["misses"], "0");
+
+    // readers report how long reads took
+    assert!(nodes.values().all(|n| n.read_latency.is_none()));
+    let mut jq = g.view("j").await.unwrap();
+    assert_eq!(jq.lookup(&[1.into()], true).await.unwrap().len(), 1);
+    assert!(jq.lookup(&[2.into()], true).await.unwrap().is_empty());
+    let stats = g.statistics().await.unwrap();
+    let latencies: Vec<_> = stats
+        .values()
+        .flat_map(|(_, nodes)| nodes.values())
+        .filter_map(|n| n.read_latency.as_ref())
+        .collect();
+    assert_eq!(latencies.iter().map(|l| l.count).sum::<u64>(), 2);
+    assert!(latencies.iter().all(|l| l.p50 <= l.max));
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn it_traces_writes() {
+    let mut g = start_simple("it_traces_writes").await;
+    let a = g
+        .migrate(|mig| {
+            let a = mig.add_base("a", &["a", "b"], Base::new(vec![]).with_key(vec![0]));
+            mig.maintain_anonymous(a, &[0]);
+            a
+        })
+        .await;
+
+    let mut muta = g.table("a").await.unwrap();
+    muta.trace_writes(Some(42));
+    muta.insert(vec![1.into(), 2.into()]).await.unwrap();
+    muta.trace_writes(None);
+    muta.insert(vec![2.into(), 4.into()]).await.unwrap();
+    sleep().await;
+
+    let events = g.trace(42).await.unwrap();
+    assert!(events.len() > 1);
+    // every node on the write's way from the base to the reader reports it
+    assert!(events.iter().any(|e| e.node == a));
+    assert!(events.iter().all(|e| e.records_in == 1));
+    assert!(events.windows(2).all(|w| w[0].at <= w[1].at));
+
+    // the untraced write left no events behind
+    assert!(g.trace(43).await.unwrap().is_empty());
+    assert_eq!(g.trace(42).await.unwrap().len(), events.len());
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn domain_amend_migration() {
     // set up graph
@@ -2409,21 +3350,32 @@ async fn live_writes() {
     let mut add = g.table("vote").await.unwrap();
 
     let ids = 1000;
-    let votes = 7;
+    let rounds = Arc::new(AtomicUsize::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
 
-    // continuously write to vote
+    // continuously write to vote, one vote for every id per round
     let (tx, rx) = tokio::sync::oneshot::channel();
-    tokio::spawn(async move {
-        // we need to use a batch putter because otherwise we'd wait for 7000 batch intervals
-        let fut =
-            add.perform_all((0..votes).flat_map(|_| (0..ids).map(|i| vec![0.into(), i.into()])));
-        tx.send(fut.await.unwrap()).unwrap();
-    });
+    {
+        let rounds = rounds.clone();
+        let stop = stop.clone();
+        tokio::spawn(async move {
+            while !stop.load(Ordering::SeqCst) {
+                // we need to use a batch putter because otherwise we'd wait for a batch interval
+                // for every single vote
+                add.perform_all((0..ids).map(|i| vec![0.into(), i.into()]))
+                    .await
+                    .unwrap();
+                rounds.fetch_add(1, Ordering::SeqCst);
+            }
+            tx.send(()).unwrap();
+        });
+    }
 
     // let a few writes through to make migration take a while
     sleep().await;
 
     // now do a migration that's going to have to copy state
+    let before = rounds.load(Ordering::SeqCst);
     let _ = g
         .migrate(move |mig| {
             let vc2 = mig.add_ingredient(
@@ -2435,13 +3387,24 @@ async fn live_writes() {
             vc2
         })
         .await;
+    let after = rounds.load(Ordering::SeqCst);
 
     let mut vc2_state = g.view("votecount2").await.unwrap();
 
-    // TODO: check that the writer did indeed complete writes during the migration
+    // writes should keep flowing while the new view is populated
+    assert!(before > 0);
+    assert!(
+        after > before,
+        "writer made no progress during the migration"
+    );
 
-    // wait for writer to finish
+    // let some writes go to the new view after the cutover, then stop the writer
+    while rounds.load(Ordering::SeqCst) <= after {
+        sleep().await;
+    }
+    stop.store(true, Ordering::SeqCst);
     rx.await.unwrap();
+    let votes = rounds.load(Ordering::SeqCst);
 
     // allow the system to catch up with the last writes
     sleep().await;
@@ -406,6 +406,9 @@ pub(crate) struct Config {
     pub(crate) quorum: usize,
     pub(crate) reuse: ReuseConfigType,
     pub(crate) threads: Option<usize>,
+    /// Stop polling inputs and acknowledging base writes in a domain while more than this many
+    /// packets are waiting to be sent to other domains.
+    pub(crate) outbox_high_water: usize,
 }
 impl Default for Config {
     fn default() -> Self {
@@ -419,6 +422,8 @@ impl Default for Config {
             domain_config: DomainConfig {
                 concurrent_replays: 512,
                 replay_batch_timeout: time::Duration::new(0, 100_000),
+                slow_update_threshold: None,
+                large_fanout_threshold: None,
             },
             persistence: Default::default(),
             heartbeat_every: time::Duration::from_secs(1),
@@ -429,6 +434,7 @@ impl Default for Config {
             threads: Some(2),
             #[cfg(not(any(debug_assertions, test)))]
             threads: None,
+            outbox_high_water: 4096,
         }
     }
 }
@@ -45,12 +45,37 @@ fn main() {
                 .default_value("100000")
                 .help("Time to wait before processing a merged packet, in nanoseconds."),
         )
+        .arg(
+            Arg::with_name("queue-capacity")
+                .long("queue-capacity")
+                .takes_value(true)
+                .default_value("8192")
+                .help("Number of rows to merge into a base table packet before processing it."),
+        )
+        .arg(
+            Arg::with_name("slow-update")
+                .long("slow-update")
+                .takes_value(true)
+                .help("Log updates that take longer than this to process in a node [in ms]."),
+        )
+        .arg(
+            Arg::with_name("large-fanout")
+                .long("large-fanout")
+                .takes_value(true)
+                .help("Log updates that a node turns into more than this many records."),
+        )
         .arg(
             Arg::with_name("log-dir")
                 .long("log-dir")
                 .takes_value(true)
                 .help("Absolute path to the directory where the log files will be written."),
         )
+        .arg(
+            Arg::with_name("db-dir")
+                .long("db-dir")
+                .takes_value(true)
+                .help("Absolute path to the directory where persistent base tables are stored."),
+        )
         .arg(
             Arg::with_name("zookeeper")
                 .short("z")
@@ -119,6 +144,7 @@ fn main() {
     let quorum = value_t_or_exit!(matches, "quorum", usize);
     let persistence_threads = value_t_or_exit!(matches, "persistence-threads", i32);
     let flush_ns = value_t_or_exit!(matches, "flush-timeout", u32);
+    let queue_capacity = value_t_or_exit!(matches, "queue-capacity", usize);
     let sharding = match value_t_or_exit!(matches, "shards", usize) {
         0 => None,
         x => Some(x),
@@ -141,6 +167,13 @@ fn main() {
     if matches.is_present("noreuse") {
         builder.set_reuse(ReuseConfigType::NoReuse);
     }
+    if matches.is_present("slow-update") {
+        let ms = value_t_or_exit!(matches, "slow-update", u64);
+        builder.set_slow_update_threshold(Duration::from_millis(ms));
+    }
+    if matches.is_present("large-fanout") {
+        builder.set_large_fanout_threshold(value_t_or_exit!(matches, "large-fanout", usize));
+    }
 
     let mut persistence_params = noria_server::PersistenceParameters::new(
         match durability {
@@ -156,6 +189,8 @@ fn main() {
     persistence_params.log_dir = matches
         .value_of("log-dir")
         .and_then(|p| Some(PathBuf::from(p)));
+    persistence_params.db_dir = matches.value_of("db-dir").map(PathBuf::from);
+    persistence_params.queue_capacity = queue_capacity;
     builder.set_persistence(persistence_params);
 
     if verbose {
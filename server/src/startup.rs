@@ -9,7 +9,9 @@ use futures_util::{
 };
 use hyper::{self, header::CONTENT_TYPE, Method, StatusCode};
 use noria::consensus::Authority;
-use noria::ControllerDescriptor;
+use noria::{ControllerDescriptor, ControllerHandle, DataType, Table, View};
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::io;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
@@ -218,12 +220,73 @@ async fn listen_internal(
     }
 }
 
-struct ExternalServer<A: Authority>(
+struct ExternalServer<A: Authority + 'static>(
     tokio::sync::mpsc::Sender<()>,
     UnboundedSender<Event>,
     Arc<A>,
+    Arc<tokio::sync::Mutex<RestHandles<A>>>,
 );
 
+/// The handles used to serve the REST endpoints. They are kept across requests so that every
+/// request does not have to set up new connections to the controller and the domains.
+struct RestHandles<A: Authority + 'static> {
+    authority: Arc<A>,
+    controller: Option<ControllerHandle<A>>,
+    views: HashMap<String, View>,
+    tables: HashMap<String, Table>,
+}
+
+impl<A: Authority + 'static> RestHandles<A> {
+    fn new(authority: Arc<A>) -> Self {
+        RestHandles {
+            authority,
+            controller: None,
+            views: HashMap::new(),
+            tables: HashMap::new(),
+        }
+    }
+
+    async fn controller(&mut self) -> Result<&mut ControllerHandle<A>, (StatusCode, String)> {
+        if self.controller.is_none() {
+            let ch = ControllerHandle::make(self.authority.clone())
+                .await
+                .map_err(internal)?;
+            self.controller = Some(ch);
+        }
+        let ch = self.controller.as_mut().unwrap();
+        ch.ready().await.map_err(internal)?;
+        Ok(ch)
+    }
+
+    async fn view(&mut self, name: &str) -> Result<View, (StatusCode, String)> {
+        if let Some(view) = self.views.get(name) {
+            return Ok(view.clone());
+        }
+        let view = self
+            .controller()
+            .await?
+            .view(name)
+            .await
+            .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+        self.views.insert(name.to_string(), view.clone());
+        Ok(view)
+    }
+
+    async fn table(&mut self, name: &str) -> Result<Table, (StatusCode, String)> {
+        if let Some(table) = self.tables.get(name) {
+            return Ok(table.clone());
+        }
+        let table = self
+            .controller()
+            .await?
+            .table(name)
+            .await
+            .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+        self.tables.insert(name.to_string(), table.clone());
+        Ok(table)
+    }
+}
+
 async fn listen_external<A: Authority + 'static>(
     alive: tokio::sync::mpsc::Sender<()>,
     valve: Valve,
@@ -234,14 +297,19 @@ async fn listen_external<A: Authority + 'static>(
     let on = valve.wrap(on.incoming());
     use hyper::{service::make_service_fn, Body, Request, Response};
     use tower::Service;
-    impl<A: Authority> Clone for ExternalServer<A> {
+    impl<A: Authority + 'static> Clone for ExternalServer<A> {
         // Needed due to #26925
         fn clone(&self) -> Self {
-            ExternalServer(self.0.clone(), self.1.clone(), self.2.clone())
+            ExternalServer(
+                self.0.clone(),
+                self.1.clone(),
+                self.2.clone(),
+                self.3.clone(),
+            )
         }
     }
 
-    impl<A: Authority> Service<Request<Body>> for ExternalServer<A> {
+    impl<A: Authority + 'static> Service<Request<Body>> for ExternalServer<A> {
         type Response = Response<Body>;
         type Error = hyper::Error;
         type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
@@ -262,6 +330,13 @@ async fn listen_external<A: Authority + 'static>(
                             .body(hyper::Body::from(include_str!("graph.html")));
                         return Box::pin(async move { Ok(res.unwrap()) });
                     }
+                    path if path.starts_with("/view/") => {
+                        let handles = self.3.clone();
+                        let path = path["/view/".len()..].to_string();
+                        return Box::pin(async move {
+                            Ok(rest_reply(res, view_lookup(handles, &path).await))
+                        });
+                    }
                     path if path.starts_with("/zookeeper/") => {
                         let res = match self.2.try_read(&format!("/{}", &path[11..])) {
                             Ok(Some(data)) => res
@@ -275,6 +350,17 @@ async fn listen_external<A: Authority + 'static>(
                 }
             }
 
+            if let Method::POST = *req.method() {
+                if req.uri().path().starts_with("/table/") {
+                    let handles = self.3.clone();
+                    let table = req.uri().path()["/table/".len()..].to_string();
+                    return Box::pin(async move {
+                        let body = hyper::body::to_bytes(req.into_body()).await?;
+                        Ok(rest_reply(res, table_insert(handles, &table, &body).await))
+                    });
+                }
+            }
+
             let method = req.method().clone();
             let path = req.uri().path().to_string();
             let query = req.uri().query().map(ToOwned::to_owned);
@@ -315,7 +401,8 @@ async fn listen_external<A: Authority + 'static>(
         }
     }
 
-    let service = ExternalServer(alive, event_tx, authority);
+    let handles = Arc::new(tokio::sync::Mutex::new(RestHandles::new(authority.clone())));
+    let service = ExternalServer(alive, event_tx, authority, handles);
     hyper::server::Server::builder(hyper::server::accept::from_stream(on))
         .serve(make_service_fn(move |_| {
             let s = service.clone();
@@ -323,3 +410,156 @@ async fn listen_external<A: Authority + 'static>(
         }))
         .await
 }
+
+/// Convert a value into its natural JSON representation for the REST endpoints.
+fn datatype_to_json(v: &DataType) -> serde_json::Value {
+    use serde_json::Value;
+    match *v {
+        DataType::None => Value::Null,
+        DataType::Int(n) => n.into(),
+        DataType::UnsignedInt(n) => n.into(),
+        DataType::BigInt(n) => n.into(),
+        DataType::UnsignedBigInt(n) => n.into(),
+        DataType::Real(..) | DataType::Float(..) => f64::from(v).into(),
+        DataType::Bool(b) => b.into(),
+        DataType::Text(..) | DataType::TinyText(..) => <&str>::from(v).into(),
+        _ => v.to_string().into(),
+    }
+}
+
+/// Convert a JSON value from a REST request into a value.
+fn json_to_datatype(v: &serde_json::Value) -> Result<DataType, String> {
+    use serde_json::Value;
+    match *v {
+        Value::Null => Ok(DataType::None),
+        Value::Bool(b) => Ok(b.into()),
+        Value::Number(ref n) => {
+            if let Some(n) = n.as_i64() {
+                Ok(i32::try_from(n)
+                    .map(DataType::from)
+                    .unwrap_or_else(|_| n.into()))
+            } else if let Some(n) = n.as_u64() {
+                Ok(n.into())
+            } else {
+                Ok(n.as_f64().unwrap().into())
+            }
+        }
+        Value::String(ref s) => Ok(s.as_str().into()),
+        _ => Err(format!("cannot use {} as a column value", v)),
+    }
+}
+
+/// Look up a key in a view, where `path` is the view name followed by one percent-encoded path
+/// segment per key column. If the view has a SQL schema, each segment is parsed as a value of its
+/// key column's type. Otherwise, segments are parsed as JSON values, and anything that is not
+/// valid JSON is used as a string.
+async fn view_lookup<A: Authority + 'static>(
+    handles: Arc<tokio::sync::Mutex<RestHandles<A>>>,
+    path: &str,
+) -> Result<String, (StatusCode, String)> {
+    let segments = path
+        .split('/')
+        .map(|s| {
+            percent_encoding::percent_decode_str(s)
+                .decode_utf8()
+                .map_err(|e| format!("invalid path segment {}: {}", s, e))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let name: &str = &segments[0];
+    let segments = &segments[1..];
+
+    let mut view = handles.lock().await.view(name).await?;
+    if segments.len() != view.key().len() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "expected {} key columns, got {}",
+                view.key().len(),
+                segments.len()
+            ),
+        ));
+    }
+    let key = match view.schema() {
+        Some(schema) => view
+            .key()
+            .iter()
+            .zip(segments)
+            .map(|(&coli, k)| {
+                DataType::parse_as(k, &schema[coli].sql_type).map_err(|e| {
+                    format!(
+                        "column {}: could not parse \"{}\": {}",
+                        view.columns()[coli],
+                        k,
+                        e
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>(),
+        None => segments
+            .iter()
+            .map(|k| match serde_json::from_str(k) {
+                Ok(v) => json_to_datatype(&v),
+                Err(_) => Ok(DataType::from(&**k)),
+            })
+            .collect::<Result<Vec<_>, _>>(),
+    }
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let rows = match view.lookup(&key, true).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            // the view may have been moved or removed, so fetch it again next time
+            handles.lock().await.views.remove(name);
+            return Err(internal(e));
+        }
+    };
+    let rows: Vec<Vec<_>> = rows
+        .into_iter()
+        .map(|row| row.iter().map(datatype_to_json).collect())
+        .collect();
+    Ok(serde_json::to_string(&rows).unwrap())
+}
+
+/// Insert rows into a base table, where `body` is a JSON array of rows.
+async fn table_insert<A: Authority + 'static>(
+    handles: Arc<tokio::sync::Mutex<RestHandles<A>>>,
+    name: &str,
+    body: &[u8],
+) -> Result<String, (StatusCode, String)> {
+    let rows: Vec<Vec<serde_json::Value>> =
+        serde_json::from_slice(body).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let rows = rows
+        .iter()
+        .map(|row| row.iter().map(json_to_datatype).collect())
+        .collect::<Result<Vec<Vec<_>>, _>>()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let mut table = handles.lock().await.table(name).await?;
+    if let Err(e) = table.insert_many(rows).await {
+        // the table may have been moved or removed, so fetch it again next time
+        handles.lock().await.tables.remove(name);
+        return Err(internal(e));
+    }
+    Ok(String::from("null"))
+}
+
+fn internal<E: fmt::Display>(e: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn rest_reply(
+    res: hyper::http::response::Builder,
+    reply: Result<String, (StatusCode, String)>,
+) -> hyper::Response<hyper::Body> {
+    match reply {
+        Ok(json) => res
+            .header(CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(hyper::Body::from(json)),
+        Err((status, e)) => res
+            .status(status)
+            .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(hyper::Body::from(e)),
+    }
+    .unwrap()
+}
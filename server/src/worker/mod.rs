@@ -147,8 +147,7 @@ pub(super) async fn main(
                 .await;
 
                 if let Err(e) = ctrl {
-                    error!(log, "failed to connect to controller");
-                    eprintln!("{:?}", e);
+                    error!(log, "failed to connect to controller"; "error" => ?e);
                 } else {
                     // now we can start accepting dataflow messages
                     worker_state = InstanceState::Active {
@@ -186,6 +185,7 @@ async fn listen_df<'a>(
     // first, try to connect to controller
     let ctrl = tokio::net::TcpStream::connect(&desc.worker_addr).await?;
     let ctrl_addr = ctrl.local_addr()?;
+    let log = log.new(o!("epoch" => ?state.epoch));
     info!(log, "connected to controller"; "src" => ?ctrl_addr);
 
     let log_prefix = state.config.persistence.log_prefix.clone();
@@ -201,6 +201,7 @@ async fn listen_df<'a>(
     // extract important things from state config
     let epoch = state.epoch;
     let heartbeat_every = state.config.heartbeat_every;
+    let outbox_high_water = state.config.outbox_high_water;
 
     let (ctrl_tx, mut ctrl_rx) = tokio::sync::mpsc::unbounded_channel();
 
@@ -213,6 +214,7 @@ async fn listen_df<'a>(
     // start controller message handler
     let mut ctrl = AsyncBincodeWriter::from(ctrl).for_async();
     let a = alive.clone();
+    let ctrl_log = log.clone();
     tokio::spawn(async move {
         let _alive = a;
         while let Some(cm) = ctrl_rx.next().await {
@@ -226,7 +228,7 @@ async fn listen_df<'a>(
             {
                 // if the controller goes away, another will be elected, and the worker will be
                 // restarted, so there's no reason to do anything too drastic here.
-                eprintln!("controller went away: {:?}", e);
+                warn!(ctrl_log, "controller went away"; "error" => ?e);
             }
         }
     });
@@ -237,6 +239,7 @@ async fn listen_df<'a>(
         valve.clone(),
         rport,
         readers.clone(),
+        log.clone(),
     ));
 
     // and tell the controller about us
@@ -314,11 +317,20 @@ async fn listen_df<'a>(
                 });
 
                 let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+                // other domains' sends wait while this one is as far behind as its own outbox
+                // may get, so that it pushes back on them just like remote domains do
+                let (data_tx, data_rx) = tokio::sync::mpsc::channel(outbox_high_water);
 
                 // need to register the domain with the local channel coordinator.
                 // local first to ensure that we don't unnecessarily give away remote for a
                 // local thing if there's a race
-                coord.insert_local((idx, shard), tx);
+                coord.insert_local(
+                    (idx, shard),
+                    channel::LocalChannel {
+                        control: tx,
+                        data: data_tx,
+                    },
+                );
                 coord.insert_remote((idx, shard), addr);
 
                 tokio::task::block_in_place(|| {
@@ -330,6 +342,8 @@ async fn listen_df<'a>(
                     d,
                     on,
                     rx,
+                    data_rx,
+                    outbox_high_water,
                     ctrl_tx.clone(),
                     log.clone(),
                     coord.clone(),
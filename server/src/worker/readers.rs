@@ -9,7 +9,7 @@ use futures_util::{
     future::{FutureExt, TryFutureExt},
     stream::{StreamExt, TryStreamExt},
 };
-use noria::{ReadQuery, ReadReply, Tagged};
+use noria::{ReadLimit, ReadQuery, ReadReply, Tagged};
 use pin_project::pin_project;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -30,6 +30,8 @@ const RETRY_TIMEOUT: time::Duration = time::Duration::from_micros(100);
 const TRIGGER_TIMEOUT_MS: u64 = 20;
 
 task_local! {
+    // every connection keeps its own clones of the reader handles it reads from, which among other
+    // things means it records read latencies without contending with other connections
     static READERS: RefCell<HashMap<
         (NodeIndex, usize),
         SingleReadHandle,
@@ -60,6 +62,7 @@ pub(super) async fn listen(
     valve: Valve,
     mut on: tokio::net::TcpListener,
     readers: Readers,
+    log: slog::Logger,
 ) {
     let mut stream = valve.wrap(on.incoming()).into_stream();
     while let Some(stream) = stream.next().await {
@@ -72,6 +75,8 @@ pub(super) async fn listen(
         let readers = readers.clone();
         stream.set_nodelay(true).expect("could not set TCP_NODELAY");
         let alive = alive.clone();
+        let log = log.new(o!("client" => ?stream.peer_addr().ok()));
+        let retry_log = log.clone();
 
         // future that ensures all blocking reads are handled in FIFO order
         // and avoid hogging the executors with read retries
@@ -84,7 +89,7 @@ pub(super) async fn listen(
             loop {
                 if let Some((ref mut blocking, _)) = pending {
                     // we have a pending read — see if it can complete
-                    if let Poll::Ready(res) = blocking.check() {
+                    if let Poll::Ready(res) = blocking.check(&retry_log) {
                         // it did! let's tell the caller.
                         let (_, ack) = pending.take().expect("we matched on Some above");
                         // if this errors, the client just went away
@@ -124,7 +129,7 @@ pub(super) async fn listen(
         );
         tokio::spawn(
             server
-                .map_err(|e| {
+                .map_err(move |e| {
                     match e {
                         server::Error::Service(()) => {
                             // server is shutting down -- no need to report this error
@@ -142,7 +147,7 @@ pub(super) async fn listen(
                             }
                         }
                     }
-                    eprintln!("!!! reader client protocol error: {:?}", e);
+                    error!(log, "reader client protocol error"; "error" => ?e);
                 })
                 .map(move |r| {
                     let _ = alive;
@@ -176,6 +181,17 @@ where
     SerializedReadReplyBatch(v)
 }
 
+fn lookup(
+    reader: &SingleReadHandle,
+    key: &[DataType],
+    limit: Option<ReadLimit>,
+    args: &[DataType],
+) -> Result<Option<SerializedReadReplyBatch>, ()> {
+    reader
+        .try_find_filtered_and(key, args, limit, |rs| serialize(rs))
+        .map(|r| r.0)
+}
+
 fn handle_message(
     m: Tagged<ReadQuery>,
     s: &Readers,
@@ -187,7 +203,10 @@ fn handle_message(
             target,
             mut keys,
             block,
+            limit,
+            args,
         } => {
+            let start = time::Instant::now();
             let immediate = READERS.with(|readers_cache| {
                 let mut readers_cache = readers_cache.borrow_mut();
                 let reader = readers_cache.entry(target).or_insert_with(|| {
@@ -207,7 +226,7 @@ fn handle_message(
                         ret.push(SerializedReadReplyBatch::empty());
                         return false;
                     }
-                    let rs = reader.try_find_and(key, |rs| serialize(rs)).map(|r| r.0);
+                    let rs = lookup(reader, key, limit, &args);
                     match rs {
                         Ok(Some(rs)) => {
                             // immediate hit!
@@ -239,6 +258,7 @@ fn handle_message(
                 if keys.is_empty() {
                     // we hit on all the keys!
                     assert!(pending.is_empty());
+                    reader.record_latency(start.elapsed());
                     return Ok(Tagged {
                         tag,
                         v: ReadReply::Normal(Ok(ret)),
@@ -247,6 +267,10 @@ fn handle_message(
 
                 // trigger backfills for all the keys we missed on
                 reader.trigger(keys.iter().map(Vec::as_slice));
+                if !block {
+                    // the caller gets what we have right away
+                    reader.record_latency(start.elapsed());
+                }
 
                 Err((keys, ret, pending))
             });
@@ -268,12 +292,15 @@ fn handle_message(
                                 tag,
                                 target,
                                 keys,
+                                limit,
+                                args,
                                 pending,
                                 read: ret,
                                 truth: s.clone(),
                                 trigger_timeout: trigger,
                                 next_trigger: now,
                                 first: now,
+                                started: start,
                             },
                             tx,
                         ));
@@ -316,6 +343,10 @@ struct BlockingRead {
     read: Vec<SerializedReadReplyBatch>,
     // keys we have yet to read
     keys: Vec<Vec<DataType>>,
+    // restriction on the rows to read for each key
+    limit: Option<ReadLimit>,
+    // arguments for the reader's filter
+    args: Vec<DataType>,
     // index in self.read that each entyr in keys corresponds to
     pending: Vec<usize>,
     truth: Readers,
@@ -323,6 +354,8 @@ struct BlockingRead {
     trigger_timeout: time::Duration,
     next_trigger: time::Instant,
     first: time::Instant,
+    // when the read arrived, so that we can record its latency once it completes
+    started: time::Instant,
 }
 
 impl std::fmt::Debug for BlockingRead {
@@ -332,16 +365,22 @@ impl std::fmt::Debug for BlockingRead {
             .field("target", &self.target)
             .field("read", &self.read)
             .field("keys", &self.keys)
+            .field("limit", &self.limit)
+            .field("args", &self.args)
             .field("pending", &self.pending)
             .field("trigger_timeout", &self.trigger_timeout)
             .field("next_trigger", &self.next_trigger)
             .field("first", &self.first)
+            .field("started", &self.started)
             .finish()
     }
 }
 
 impl BlockingRead {
-    fn check(&mut self) -> Poll<Result<Tagged<ReadReply<SerializedReadReplyBatch>>, ()>> {
+    fn check(
+        &mut self,
+        log: &slog::Logger,
+    ) -> Poll<Result<Tagged<ReadReply<SerializedReadReplyBatch>>, ()>> {
         READERS.with(|readers_cache| {
             let mut readers_cache = readers_cache.borrow_mut();
             let s = &self.truth;
@@ -364,7 +403,7 @@ impl BlockingRead {
 
             while let Some(read_i) = self.pending.pop() {
                 let key = self.keys.pop().expect("pending.len() == keys.len()");
-                match reader.try_find_and(&key, |rs| serialize(rs)).map(|r| r.0) {
+                match lookup(reader, &key, self.limit, &self.args) {
                     Ok(Some(rs)) => {
                         read[read_i] = rs;
                     }
@@ -399,11 +438,14 @@ impl BlockingRead {
                 let waited = now - self.first;
                 self.first = now;
                 if waited > time::Duration::from_secs(7) {
-                    eprintln!(
-                        "warning: read has been stuck waiting on {:?} for {:?}",
-                        self.keys, waited
-                    );
+                    warn!(log, "read has been stuck waiting on keys";
+                          "node" => self.target.0.index(),
+                          "shard" => self.target.1,
+                          "keys" => ?self.keys,
+                          "waited" => ?waited);
                 }
+            } else {
+                reader.record_latency(self.started.elapsed());
             }
 
             Ok(())
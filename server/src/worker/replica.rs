@@ -20,7 +20,7 @@ use futures_util::{
 use noria::channel::{DualTcpStream, CONNECTION_FROM_BASE};
 use noria::internal::DomainIndex;
 use noria::internal::LocalOrNot;
-use noria::{Input, Tagged};
+use noria::{Input, Tagged, WriteReply};
 use pin_project::pin_project;
 use slog;
 use std::collections::{HashMap, VecDeque};
@@ -74,6 +74,14 @@ pub(super) struct Replica {
     first_byte: FuturesUnordered<FirstByte>,
 
     locals: tokio::sync::mpsc::UnboundedReceiver<Box<Packet>>,
+    local_inputs: tokio::sync::mpsc::Receiver<Box<Packet>>,
+
+    /// Stop taking in new work, and stop acknowledging base writes, while more than this many
+    /// packets are waiting to be sent to other domains. Since local channels are bounded, and
+    /// remote domains stop reading from their sockets, this pushes back on upstream domains one
+    /// hop at a time until it reaches the clients writing to base tables, which only have a
+    /// bounded number of unacknowledged writes in flight.
+    outbox_high_water: usize,
 
     #[pin]
     inputs: StreamUnordered<
@@ -106,6 +114,8 @@ impl Replica {
         mut domain: Domain,
         on: tokio::net::TcpListener,
         locals: tokio::sync::mpsc::UnboundedReceiver<Box<Packet>>,
+        local_inputs: tokio::sync::mpsc::Receiver<Box<Packet>>,
+        outbox_high_water: usize,
         ctrl_tx: tokio::sync::mpsc::UnboundedSender<CoordinationPayload>,
         log: slog::Logger,
         cc: Arc<ChannelCoordinator>,
@@ -121,6 +131,8 @@ impl Replica {
             incoming: Strawpoll::from(on),
             first_byte: FuturesUnordered::new(),
             locals,
+            local_inputs,
+            outbox_high_water,
             log: log.new(o! {"id" => id}),
             inputs: Default::default(),
             outputs: Default::default(),
@@ -147,7 +159,7 @@ impl Replica {
             let mut stream = Pin::new(&mut inputs[streami]);
             let mut sent = 0;
 
-            for &tag in &conn.tag_acks {
+            for (tag, reply) in &conn.tag_acks {
                 match stream.as_mut().poll_ready(cx) {
                     Poll::Ready(Ok(())) => {}
                     Poll::Pending => break,
@@ -157,7 +169,10 @@ impl Replica {
                     }
                 }
 
-                if let Err(e) = stream.as_mut().start_send(Tagged { tag, v: () }) {
+                if let Err(e) = stream.as_mut().start_send(Tagged {
+                    tag: *tag,
+                    v: reply.clone(),
+                }) {
                     // start_send shouldn't generally error
                     err.push(e.into());
                     break;
@@ -436,8 +451,8 @@ struct ConnState {
     // number of unacked inputs
     unacked: usize,
 
-    // unsent acks (value is the tag)
-    tag_acks: Vec<u32>,
+    // unsent acks (tag, and the keys of any inserts the base rejected)
+    tag_acks: Vec<(u32, WriteReply)>,
 
     // epoch counter for each stream index (since they're re-used)
     epoch: usize,
@@ -510,13 +525,13 @@ impl Outboxes {
 }
 
 impl Executor for Outboxes {
-    fn ack(&mut self, id: SourceChannelIdentifier) {
+    fn ack(&mut self, id: SourceChannelIdentifier, reply: WriteReply) {
         self.dirty = true;
         let mut c = &mut self.connections[id.token];
         if id.epoch == c.epoch {
             // if the epoch doesn't match, the stream was closed and a new one has been established
             // note that this only matters for connections that do not wait for all acks!
-            c.tag_acks.push(id.tag);
+            c.tag_acks.push((id.tag, reply));
 
             // NOTE: it's a little sad we can't crash on underflow here.
             // it is because if a send fails, we set c.unacked = 0, and should the domain _then_
@@ -538,6 +553,10 @@ impl Executor for Outboxes {
         self.dirty = true;
         self.domains.entry(dest).or_default().push_back(m);
     }
+
+    fn queued(&self) -> usize {
+        self.domains.values().map(VecDeque::len).sum()
+    }
 }
 
 impl Future for Replica {
@@ -575,9 +594,15 @@ impl Future for Replica {
             // channel and once from the set of remote channels. this biases slightly in favor of
             // local sends, without starving either. we also stop alternating once either source is
             // depleted.
+            //
+            // while our outbox is above the high-water mark, we only read control messages from
+            // the local channel, and leave everything else where it is. local senders then fill
+            // up our bounded data channel, and remote ones their sockets, so they too back up and
+            // stop reading their inputs.
             let mut local_done = false;
             let mut remote_done = false;
             let mut check_local = true;
+            let mut throttled = false;
             let mut this = self.as_mut().project();
             let d = this.domain;
             let out = this.out;
@@ -616,8 +641,18 @@ impl Future for Replica {
             }
 
             for _ in 0..FORCE_INPUT_YIELD_EVERY {
+                let full = out.queued() >= *this.outbox_high_water;
+                throttled |= full;
+
                 if !local_done && (check_local || remote_done) {
-                    match this.locals.poll_recv(cx) {
+                    let mut next = this.locals.poll_recv(cx);
+                    if let Poll::Pending = next {
+                        if !full {
+                            next = this.local_inputs.poll_recv(cx);
+                        }
+                    }
+
+                    match next {
                         Poll::Ready(Some(packet)) => {
                             process!(*this.retry, out, packet, |p| d
                                 .on_event(out, PollEvent::Process(p),));
@@ -634,6 +669,11 @@ impl Future for Replica {
                     }
                 }
 
+                if !remote_done && full {
+                    // leave remote inputs in their sockets until downstream catches up
+                    remote_done = true;
+                }
+
                 if !remote_done && (!check_local || local_done) {
                     match this.inputs.as_mut().poll_next(cx) {
                         Poll::Ready(Some((StreamYield::Item(Ok(packet)), _))) => {
@@ -685,12 +725,16 @@ impl Future for Replica {
                 .try_flush(cx)
                 .context("downstream flush (after)")?;
 
-            // send acks
-            self.as_mut().try_acks(cx)?;
+            // send acks, unless downstream domains are falling behind. once they drain our
+            // outbox, try_flush will have registered for a wakeup, and we'll ack then.
+            let full = self.out.queued() >= self.outbox_high_water;
+            if !full {
+                self.as_mut().try_acks(cx)?;
+            }
 
-            if !local_done || !remote_done {
-                // we're yielding voluntarily to not block the executor and must ensure we wake
-                // up again
+            if !local_done || !remote_done || (throttled && !full) {
+                // we're either yielding voluntarily to not block the executor, or we left inputs
+                // unread that we can now get to, and must ensure we wake up again
                 cx.waker().wake_by_ref();
             }
 